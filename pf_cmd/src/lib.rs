@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use futures_util::pin_mut;
 use futures_util::StreamExt;
 use regex::Regex;
@@ -16,9 +16,113 @@ fn validate_date(val: &str) -> Result<String, String> {
     }
 }
 
-/// Scans WordPress websites to find videos.
+fn parse_mime_type(val: &str) -> Result<(String, String), String> {
+    match val.split_once('=') {
+        Some((ext, mime)) if !ext.is_empty() && mime.contains('/') => {
+            Ok((ext.to_string(), mime.to_string()))
+        }
+        Some(_) => Err(String::from(
+            "Invalid MIME type. Use an extension and a MIME type in the form ext=type/subtype.",
+        )),
+        None => pf_lib::MimeRegistry::known(val)
+            .map(|mime| (val.to_string(), mime.to_string()))
+            .ok_or(format!(
+                "Unknown extension {val}. Add its MIME type in the form {val}=type/subtype."
+            )),
+    }
+}
+
+fn parse_confidence(val: &str) -> Result<f32, String> {
+    match val.parse::<f32>() {
+        Ok(confidence) if (0.0..=1.0).contains(&confidence) => Ok(confidence),
+        _ => Err(String::from(
+            "Invalid confidence. Use a number between 0 and 1.",
+        )),
+    }
+}
+
+/// Returns the default cache directory, `$XDG_CACHE_HOME/pf` or `$HOME/.cache/pf`.
+fn default_cache_dir() -> std::path::PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pf")
+}
+
+/// Where to discover content.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Source {
+    /// WordPress REST API.
+    Api,
+    /// Sitemaps, for websites that disable the REST API.
+    Sitemap,
+    /// HTML pages listed in sitemaps or feeds or enumerated by ID, for websites that disable the REST API.
+    Html,
+    /// RSS or Atom feed, for websites that disable the REST API.
+    Feed,
+}
+
+impl From<Source> for pf_lib::FinderSource {
+    fn from(source: Source) -> Self {
+        match source {
+            Source::Api => pf_lib::FinderSource::Api,
+            Source::Sitemap => pf_lib::FinderSource::Sitemap,
+            Source::Html => pf_lib::FinderSource::Html,
+            Source::Feed => pf_lib::FinderSource::Feed,
+        }
+    }
+}
+
+/// How to discover the media attached to posts and other post types.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Attachments {
+    /// Only featured media and links in the content.
+    Skip,
+    /// Attachments embedded in the REST API responses.
+    Embed,
+    /// Attachments requested from the media endpoint for each page of posts, for websites that limit embedding.
+    Parent,
+}
+
+impl From<Attachments> for pf_lib::FinderAttachments {
+    fn from(attachments: Attachments) -> Self {
+        match attachments {
+            Attachments::Skip => pf_lib::FinderAttachments::Skip,
+            Attachments::Embed => pf_lib::FinderAttachments::Embed,
+            Attachments::Parent => pf_lib::FinderAttachments::Parent,
+        }
+    }
+}
+
+/// Kind of media to look for.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Kind {
+    /// Videos.
+    Video,
+    /// Audio files (e.g. podcasts).
+    Audio,
+    /// Original images.
+    Image,
+    /// Documents.
+    Document,
+}
+
+impl From<Kind> for pf_lib::MediaKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Video => pf_lib::MediaKind::Video,
+            Kind::Audio => pf_lib::MediaKind::Audio,
+            Kind::Image => pf_lib::MediaKind::Image,
+            Kind::Document => pf_lib::MediaKind::Document,
+        }
+    }
+}
+
+/// Scans WordPress websites to find videos, audio files, images or documents.
 ///
-/// Supported MIME types: video/mp4 and video/quicktime (.mov).
+/// Default MIME types: video/mp4 and video/quicktime (.mov) for videos, audio/mpeg (.mp3) and audio/mp4 (.m4a) for
+/// audio files, image/jpeg and image/png for images, application/pdf for documents.
 #[derive(Parser)]
 #[command(name = "pf", author, version, about)]
 pub struct Opt {
@@ -52,6 +156,132 @@ pub struct Opt {
     /// Ensures result set excludes to specific tag IDs.
     #[arg(long)]
     pub tags_exclude: Vec<u16>,
+
+    /// Post types to scan by REST base (e.g. posts, pages, media, product), or `all` for every public post type.
+    #[arg(long, value_delimiter = ',', default_values = ["posts", "media"])]
+    pub types: Vec<String>,
+
+    /// Where to discover content. Post types are only used with the REST API.
+    #[arg(long, value_enum, default_value_t = Source::Api)]
+    pub source: Source,
+
+    /// Highest ID of the `?p=ID` links enumerated by the html source when a website has neither sitemap nor feed.
+    #[arg(long, default_value_t = pf_lib::FinderEnumeration::default().max_id)]
+    pub max_id: u32,
+
+    /// Number of consecutive missing IDs after which the html source stops enumerating `?p=ID` links.
+    #[arg(long, default_value_t = pf_lib::FinderEnumeration::default().max_missing_ids)]
+    pub max_missing_ids: u32,
+
+    /// How to discover the media attached to posts and other post types, which are not always featured or linked
+    /// in their content. Only used with the REST API.
+    #[arg(long, value_enum, default_value_t = Attachments::Skip)]
+    pub attachments: Attachments,
+
+    /// Kind of media to look for.
+    #[arg(long, value_enum, default_value_t = Kind::Video, conflicts_with = "mime_types")]
+    pub kind: Kind,
+
+    /// File types to look for instead of the default MIME types of a kind, either as a known extension (e.g. webm, mkv) or
+    /// as an extension and its MIME type (e.g. mkv=video/x-matroska).
+    #[arg(long = "type", value_name = "TYPE", value_parser = parse_mime_type)]
+    pub mime_types: Vec<(String, String)>,
+
+    /// File types to look for in addition to the default MIME types of a kind or to the `--type` ones, in the same
+    /// forms as `--type` (e.g. webm, mkv=video/x-matroska).
+    #[arg(long = "add-type", value_name = "TYPE", value_parser = parse_mime_type)]
+    pub added_mime_types: Vec<(String, String)>,
+
+    /// Heuristics extracting candidate URLs: p1 (source_url of media and featured media), p2 (links in the
+    /// content and excerpt of posts), p3 (files named after the slug of posts, guessed in their upload month).
+    #[arg(long, value_delimiter = ',', value_parser = ["p1", "p2", "p3"], default_values = ["p1", "p2", "p3"])]
+    pub heuristics: Vec<String>,
+
+    /// Number of months before and after the publication and modification dates of posts in which p3 guesses
+    /// files, since files are often uploaded before a post is published or updated. Without a window, p3 only
+    /// guesses files in the month of the publication date.
+    #[arg(long, default_value_t = 0)]
+    pub month_window: u32,
+
+    /// Minimum confidence, between 0 and 1, of the candidate URLs to probe: content links (0.9) are likelier than
+    /// files named after media (0.4 to 1) and guessed from slugs (0.05 to 0.1).
+    #[arg(long, default_value_t = 0.0, value_parser = parse_confidence)]
+    pub min_confidence: f32,
+
+    /// Maximum number of candidate URLs to probe per post or media, the likeliest first.
+    #[arg(long)]
+    pub max_guesses_per_item: Option<usize>,
+
+    /// Also accepts files served as application/octet-stream or binary/octet-stream when their extension is looked
+    /// for.
+    #[arg(long)]
+    pub octet_stream: bool,
+
+    /// Confirms files by sniffing their leading bytes with a ranged GET request instead of a HEAD request, and
+    /// reports files whose content does not match their type.
+    #[arg(long)]
+    pub sniff: bool,
+
+    /// Drops files whose server rejects HEAD requests instead of retrying with a single byte range GET request.
+    #[arg(long, conflicts_with = "sniff")]
+    pub no_get_fallback: bool,
+
+    /// Maximum number of redirects to follow when confirming files.
+    #[arg(long, default_value_t = 10)]
+    pub max_redirects: usize,
+
+    /// Only follows redirects to the host of the file when confirming files.
+    #[arg(long)]
+    pub same_host_redirects: bool,
+
+    /// Rejects files whose redirects end at a host or one of its subdomains, in addition to known placeholder
+    /// hosts.
+    #[arg(long)]
+    pub placeholder_host: Vec<String>,
+
+    /// File listing the URLs already verified, one per line, which are skipped and to which new URLs are appended
+    /// once confirmed or rejected (e.g. to resume a scan).
+    #[arg(long)]
+    pub seen_file: Option<std::path::PathBuf>,
+
+    /// Directory of the cache of the files that turn out to be missing (404 or 410 status), which are not probed
+    /// again until their entry expires, and of the REST API responses [default: $XDG_CACHE_HOME/pf or
+    /// $HOME/.cache/pf].
+    #[arg(long)]
+    pub cache_dir: Option<std::path::PathBuf>,
+
+    /// Number of days after which cached missing files are probed again and cached REST API responses are dropped.
+    #[arg(long, default_value_t = 7)]
+    pub cache_ttl: u64,
+
+    /// Probes every file and requests every REST API response, without reading nor writing the cache.
+    #[arg(long, conflicts_with = "cache_dir")]
+    pub no_cache: bool,
+
+    /// Scans without requests: REST API responses are replayed from the cache and the files they list as is (e.g.
+    /// source URLs of media) are printed unless the cache knows them as missing. Guessed URLs are not verified, use
+    /// --dry-run to list them.
+    #[arg(long, conflicts_with = "no_cache")]
+    pub offline: bool,
+
+    /// Directory to record the REST API responses and probe results to (e.g. to share a bug report).
+    #[arg(long, conflicts_with_all = ["replay", "offline"])]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Directory of REST API responses and probe results recorded with --record, which are replayed without
+    /// requests.
+    #[arg(long, conflicts_with = "offline")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Lists every candidate URL with its heuristic, confidence, post or media ID and whether it would be probed,
+    /// without probing them, e.g. to tune the heuristics. Candidate URLs are not written to the seen file.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Prints one JSON object per line instead of URLs, with the MIME type and probe of the files or, with
+    /// --dry-run, the details of the candidate URLs.
+    #[arg(long)]
+    pub json: bool,
 }
 
 impl Opt {
@@ -63,6 +293,66 @@ impl Opt {
                 categories_exclude: self.categories_exclude.clone(),
                 tags_exclude: self.tags_exclude.clone(),
             },
+            source: self.source.into(),
+            attachments: self.attachments.into(),
+            mime_types: {
+                let mut mime_types = if self.mime_types.is_empty() {
+                    pf_lib::MimeRegistry::for_kind(self.kind.into())
+                } else {
+                    self.mime_types.iter().cloned().collect()
+                };
+                for (ext, mime) in &self.added_mime_types {
+                    mime_types.insert(ext, mime);
+                }
+                mime_types.with_octet_stream(self.octet_stream)
+            },
+            extractors: {
+                let mut extractors = pf_lib::Extractors::empty()
+                    .with(pf_lib::SourceUrlExtractor)
+                    .with(pf_lib::ContentExtractor)
+                    .with(pf_lib::SlugExtractor {
+                        months: self.month_window,
+                    });
+                extractors
+                    .retain(|extractor| self.heuristics.iter().any(|h| h == extractor.name()));
+                extractors
+            },
+            budget: pf_lib::FinderBudget {
+                min_confidence: self.min_confidence,
+                max_guesses_per_item: self.max_guesses_per_item,
+            },
+            retry: pf_lib::FinderRetry::default(),
+            enumeration: pf_lib::FinderEnumeration {
+                max_id: self.max_id,
+                max_missing_ids: self.max_missing_ids,
+            },
+            verifiers: pf_lib::Verifiers::default(),
+            verification: if self.sniff {
+                pf_lib::FinderVerification::Sniff
+            } else if self.no_get_fallback {
+                pf_lib::FinderVerification::HeadOnly
+            } else {
+                pf_lib::FinderVerification::Head
+            },
+            redirects: {
+                let mut redirects = pf_lib::FinderRedirects {
+                    max_hops: self.max_redirects,
+                    same_host_only: self.same_host_redirects,
+                    ..Default::default()
+                };
+                redirects
+                    .placeholder_hosts
+                    .extend(self.placeholder_host.iter().cloned());
+                redirects
+            },
+            hooks: pf_lib::FinderHooks::default(),
+            seen: pf_lib::SeenUrls::default(),
+            listed: pf_lib::SeenUrls::default(),
+            upload_bases: pf_lib::UploadBases::default(),
+            probe_cache: None,
+            response_cache: None,
+            offline: self.offline,
+            fixtures: None,
             before: self.before.clone(),
             modified_before: self.modified_before.clone(),
             after: self.after.clone(),
@@ -70,24 +360,110 @@ impl Opt {
             exclude: self.exclude.clone(),
         }
     }
+
+    /// Converts a REST base to the matching `FinderTarget`.
+    fn to_finder_target(&self, rest_base: &str) -> pf_lib::FinderTarget {
+        match rest_base {
+            "media" => pf_lib::FinderTarget::Media,
+            "posts" => pf_lib::FinderTarget::Posts {
+                categories_exclude: self.categories_exclude.clone(),
+                tags_exclude: self.tags_exclude.clone(),
+            },
+            "pages" => pf_lib::FinderTarget::Pages,
+            _ => pf_lib::FinderTarget::Custom {
+                rest_base: rest_base.to_string(),
+            },
+        }
+    }
 }
 
 /// Runs the `pf` command.
 pub async fn run(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = opt.to_finder_config();
-    print_stream(&config).await?;
-    config.target = pf_lib::FinderTarget::Media;
-    print_stream(&config).await?;
+    // Builds without any type feature have no default MIME types.
+    if config.mime_types.is_empty() {
+        return Err("No MIME type to look for. Use --type or --add-type.".into());
+    }
+    if let Some(path) = &opt.seen_file {
+        config.seen = pf_lib::SeenUrls::with_file(path)?;
+    }
+    if let Some(dir) = &opt.record {
+        config.fixtures = Some(pf_lib::Fixtures::record(dir)?);
+    }
+    if let Some(dir) = &opt.replay {
+        config.fixtures = Some(pf_lib::Fixtures::replay(dir)?);
+    }
+    // Replayed scans neither read nor write the cache.
+    if !opt.no_cache && opt.replay.is_none() {
+        let dir = opt.cache_dir.clone().unwrap_or_else(default_cache_dir);
+        let ttl = std::time::Duration::from_secs(opt.cache_ttl.saturating_mul(24 * 60 * 60));
+        let caches = pf_lib::ProbeCache::open(&dir, ttl).and_then(|probe_cache| {
+            Ok((
+                probe_cache,
+                pf_lib::ResponseCache::open(dir.join("responses"), ttl)?,
+            ))
+        });
+        match caches {
+            Ok((probe_cache, response_cache)) => {
+                config.probe_cache = Some(probe_cache);
+                config.response_cache = Some(response_cache);
+            }
+            // The default cache directory is a convenience, online scans work without it.
+            Err(e) if opt.cache_dir.is_none() && !opt.offline => eprintln!("{e}"),
+            Err(e) => return Err(e),
+        }
+    }
+    if config.source != pf_lib::FinderSource::Api {
+        return print_stream(&config, &opt).await;
+    }
+    let mut types = if opt.types.iter().any(|t| t == "all") {
+        pf_lib::post_types(&config).await?
+    } else {
+        opt.types.clone()
+    };
+    // Media are scanned first, so that the upload folders learned from their URLs are used to guess the files of
+    // the other post types.
+    types.sort_by_key(|rest_base| rest_base != "media");
+    for rest_base in types {
+        config.target = opt.to_finder_target(&rest_base);
+        print_stream(&config, &opt).await?;
+    }
     Ok(())
 }
 
-/// Consumes and prints the `find` stream.
-async fn print_stream(config: &pf_lib::FinderConfig) -> Result<(), Box<dyn std::error::Error>> {
-    let stream = pf_lib::find(config);
+/// Consumes and prints the `find_matches` stream, or the `find_candidates` stream of a dry run.
+async fn print_stream(
+    config: &pf_lib::FinderConfig,
+    opt: &Opt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if opt.dry_run {
+        let stream = pf_lib::find_candidates(config);
+        pin_mut!(stream);
+        while let Some(res) = stream.next().await {
+            match res {
+                Ok(candidate) if opt.json => println!("{}", candidate.to_json()),
+                Ok(candidate) => println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    candidate.url,
+                    candidate.heuristic,
+                    candidate.confidence,
+                    candidate
+                        .item_id
+                        .map_or("-".to_string(), |id| id.to_string()),
+                    if candidate.probed { "probe" } else { "skip" },
+                    candidate.reason
+                ),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        return Ok(());
+    }
+    let stream = pf_lib::find_matches(config);
     pin_mut!(stream);
     while let Some(res) = stream.next().await {
         match res {
-            Ok(url) => println!("{url}"),
+            Ok(found) if opt.json => println!("{}", found.to_json()),
+            Ok(found) => println!("{}", found.url),
             Err(e) => eprintln!("{e}"),
         }
     }
@@ -144,6 +520,14 @@ mod tests {
             "3",
             "--tags-exclude",
             "4",
+            "--types",
+            "posts,product",
+            "--source",
+            "sitemap",
+            "--type",
+            "webm",
+            "--type",
+            "mkv=video/x-matroska",
         ];
         let opt = Opt::parse_from(args);
         assert_eq!(opt.url, "http://example.com");
@@ -154,6 +538,213 @@ mod tests {
         assert_eq!(opt.exclude, vec![1, 2]);
         assert_eq!(opt.categories_exclude, vec![3]);
         assert_eq!(opt.tags_exclude, vec![4]);
+        assert_eq!(opt.types, vec!["posts", "product"]);
+        assert_eq!(opt.source, Source::Sitemap);
+        assert_eq!(
+            opt.mime_types,
+            vec![
+                ("webm".to_string(), "video/webm".to_string()),
+                ("mkv".to_string(), "video/x-matroska".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_mime_type() {
+        assert_eq!(
+            parse_mime_type("webm"),
+            Ok(("webm".to_string(), "video/webm".to_string()))
+        );
+        assert_eq!(
+            parse_mime_type("ogg=video/ogg"),
+            Ok(("ogg".to_string(), "video/ogg".to_string()))
+        );
+        assert!(parse_mime_type("foo").is_err());
+        assert!(parse_mime_type("foo=bar").is_err());
+        assert!(parse_mime_type("=video/ogg").is_err());
+    }
+
+    #[test]
+    fn test_opt_parsing_with_default_types() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com"]);
+        assert_eq!(opt.types, vec!["posts", "media"]);
+        assert_eq!(opt.source, Source::Api);
+        assert_eq!(opt.attachments, Attachments::Skip);
+        assert_eq!(opt.kind, Kind::Video);
+        assert_eq!(
+            opt.to_finder_config().verification,
+            pf_lib::FinderVerification::Head
+        );
+        assert!(opt.to_finder_config().mime_types == pf_lib::MimeRegistry::default());
+    }
+
+    #[test]
+    fn test_opt_parsing_with_no_get_fallback() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--no-get-fallback"]);
+        assert_eq!(
+            opt.to_finder_config().verification,
+            pf_lib::FinderVerification::HeadOnly
+        );
+    }
+
+    #[test]
+    fn test_opt_parsing_with_cache() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com"]);
+        assert_eq!(opt.cache_dir, None);
+        assert_eq!(opt.cache_ttl, 7);
+        assert!(!opt.no_cache);
+        assert!(default_cache_dir().ends_with("pf"));
+
+        assert!(Opt::try_parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--no-cache",
+            "--cache-dir",
+            "/tmp/pf"
+        ])
+        .is_err());
+        assert!(
+            Opt::try_parse_from(vec!["pf", "http://example.com", "--no-cache", "--offline"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_opt_parsing_with_record_and_replay() {
+        let opt = Opt::parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--record",
+            "/tmp/fixtures",
+        ]);
+        assert_eq!(opt.record, Some("/tmp/fixtures".into()));
+        assert_eq!(opt.replay, None);
+
+        assert!(Opt::try_parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--record",
+            "/tmp/fixtures",
+            "--replay",
+            "/tmp/fixtures"
+        ])
+        .is_err());
+        assert!(Opt::try_parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--replay",
+            "/tmp/fixtures",
+            "--offline"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_opt_parsing_with_heuristics() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com"]);
+        assert_eq!(opt.heuristics, ["p1", "p2", "p3"]);
+        assert_eq!(opt.month_window, 0);
+
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--heuristics", "p1,p2"]);
+        assert_eq!(
+            opt.to_finder_config()
+                .extractors
+                .names()
+                .collect::<Vec<_>>(),
+            ["p1", "p2"]
+        );
+
+        assert!(
+            Opt::try_parse_from(vec!["pf", "http://example.com", "--heuristics", "p4"]).is_err()
+        );
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--month-window", "3"]);
+        assert_eq!(opt.month_window, 3);
+    }
+
+    #[test]
+    fn test_opt_parsing_with_budget() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com"]);
+        assert_eq!(
+            opt.to_finder_config().budget,
+            pf_lib::FinderBudget::default()
+        );
+
+        let opt = Opt::parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--min-confidence",
+            "0.2",
+            "--max-guesses-per-item",
+            "5",
+        ]);
+        assert_eq!(
+            opt.to_finder_config().budget,
+            pf_lib::FinderBudget {
+                min_confidence: 0.2,
+                max_guesses_per_item: Some(5),
+            }
+        );
+
+        for confidence in ["1.5", "-1", "high"] {
+            assert!(Opt::try_parse_from(vec![
+                "pf",
+                "http://example.com",
+                "--min-confidence",
+                confidence
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_opt_parsing_with_added_types() {
+        let opt = Opt::parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--kind",
+            "audio",
+            "--add-type",
+            "ogg",
+            "--add-type",
+            "opus=audio/opus",
+        ]);
+        let mut mime_types = pf_lib::MimeRegistry::for_kind(pf_lib::MediaKind::Audio);
+        mime_types.insert("ogg", "audio/ogg");
+        mime_types.insert("opus", "audio/opus");
+        assert_eq!(opt.to_finder_config().mime_types, mime_types);
+
+        let opt = Opt::parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--type",
+            "webm",
+            "--add-type",
+            "mkv=video/x-matroska",
+        ]);
+        assert_eq!(
+            opt.to_finder_config().mime_types,
+            pf_lib::MimeRegistry::from_iter([("webm", "video/webm"), ("mkv", "video/x-matroska")])
+        );
+    }
+
+    #[test]
+    fn test_opt_parsing_with_kind() {
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--kind", "audio"]);
+        assert_eq!(opt.kind, Kind::Audio);
+        assert_eq!(
+            opt.to_finder_config().mime_types,
+            pf_lib::MimeRegistry::for_kind(pf_lib::MediaKind::Audio)
+        );
+
+        assert!(Opt::try_parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--kind",
+            "audio",
+            "--type",
+            "mp3"
+        ])
+        .is_err());
     }
 
     #[test]
@@ -167,6 +758,33 @@ mod tests {
             exclude: vec![1, 2],
             categories_exclude: vec![3],
             tags_exclude: vec![4],
+            types: vec![],
+            source: Source::Sitemap,
+            max_id: 500,
+            max_missing_ids: 20,
+            attachments: Attachments::Parent,
+            kind: Kind::Video,
+            mime_types: vec![("mkv".to_string(), "video/x-matroska".to_string())],
+            added_mime_types: vec![("webm".to_string(), "video/webm".to_string())],
+            heuristics: vec!["p1".to_string(), "p2".to_string()],
+            month_window: 2,
+            min_confidence: 0.5,
+            max_guesses_per_item: Some(3),
+            octet_stream: true,
+            sniff: true,
+            no_get_fallback: false,
+            max_redirects: 2,
+            same_host_redirects: true,
+            placeholder_host: vec!["hotlink.example.com".to_string()],
+            seen_file: None,
+            cache_dir: None,
+            cache_ttl: 7,
+            no_cache: false,
+            offline: true,
+            record: None,
+            replay: None,
+            dry_run: false,
+            json: true,
         };
         let config = opt.to_finder_config();
         assert_eq!(config.url, "http://example.com");
@@ -188,6 +806,58 @@ mod tests {
                 tags_exclude: vec![4],
             }
         );
+        assert_eq!(config.source, pf_lib::FinderSource::Sitemap);
+        assert_eq!(config.attachments, pf_lib::FinderAttachments::Parent);
+        assert_eq!(
+            config.enumeration,
+            pf_lib::FinderEnumeration {
+                max_id: 500,
+                max_missing_ids: 20,
+            }
+        );
+        assert_eq!(config.extractors.names().collect::<Vec<_>>(), ["p1", "p2"]);
+        assert_eq!(config.verification, pf_lib::FinderVerification::Sniff);
+        assert!(config.offline);
+        assert_eq!(config.redirects.max_hops, 2);
+        assert!(config.redirects.same_host_only);
+        assert!(config
+            .redirects
+            .placeholder_hosts
+            .contains(&"hotlink.example.com".to_string()));
+        assert!(config
+            .redirects
+            .placeholder_hosts
+            .contains(&"placehold.co".to_string()));
+        assert_eq!(
+            config.mime_types,
+            pf_lib::MimeRegistry::from_iter([("mkv", "video/x-matroska"), ("webm", "video/webm")])
+                .with_octet_stream(true)
+        );
+    }
+
+    #[test]
+    fn test_to_finder_target() {
+        let opt = Opt::parse_from(vec![
+            "pf",
+            "http://example.com",
+            "--categories-exclude",
+            "3",
+        ]);
+        assert_eq!(opt.to_finder_target("media"), pf_lib::FinderTarget::Media);
+        assert_eq!(
+            opt.to_finder_target("posts"),
+            pf_lib::FinderTarget::Posts {
+                categories_exclude: vec![3],
+                tags_exclude: vec![],
+            }
+        );
+        assert_eq!(opt.to_finder_target("pages"), pf_lib::FinderTarget::Pages);
+        assert_eq!(
+            opt.to_finder_target("product"),
+            pf_lib::FinderTarget::Custom {
+                rest_base: "product".to_string()
+            }
+        );
     }
 
     #[tokio::test]
@@ -198,13 +868,33 @@ mod tests {
                 categories_exclude: vec![],
                 tags_exclude: vec![],
             },
+            source: pf_lib::FinderSource::Api,
+            attachments: pf_lib::FinderAttachments::Skip,
+            mime_types: pf_lib::MimeRegistry::default(),
+            extractors: pf_lib::Extractors::default(),
+            budget: pf_lib::FinderBudget::default(),
+            retry: pf_lib::FinderRetry::default(),
+            enumeration: pf_lib::FinderEnumeration::default(),
+            verifiers: pf_lib::Verifiers::default(),
+            verification: pf_lib::FinderVerification::Head,
+            redirects: pf_lib::FinderRedirects::default(),
+            hooks: pf_lib::FinderHooks::default(),
+            seen: pf_lib::SeenUrls::default(),
+            listed: pf_lib::SeenUrls::default(),
+            upload_bases: pf_lib::UploadBases::default(),
+            probe_cache: None,
+            response_cache: None,
+            offline: false,
+            fixtures: None,
             before: None,
             modified_before: None,
             after: None,
             modified_after: None,
             exclude: vec![],
         };
-        let result = print_stream(&config).await;
-        assert!(result.is_ok());
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--json"]);
+        assert!(print_stream(&config, &opt).await.is_ok());
+        let opt = Opt::parse_from(vec!["pf", "http://example.com", "--dry-run"]);
+        assert!(print_stream(&config, &opt).await.is_ok());
     }
 }
@@ -7,10 +7,19 @@ fn main() {
         cfg!(feature = "mpeg"),
         cfg!(feature = "webm"),
         cfg!(feature = "wmv"),
+        cfg!(feature = "mp3"),
+        cfg!(feature = "m4a"),
+        cfg!(feature = "ogg"),
+        cfg!(feature = "wav"),
+        cfg!(feature = "jpg"),
+        cfg!(feature = "png"),
+        cfg!(feature = "gif"),
+        cfg!(feature = "webp"),
+        cfg!(feature = "pdf"),
     ];
 
+    // Types can be registered at runtime, so a build without any type feature only lacks default MIME types.
     if !features.iter().any(|&enabled| enabled) {
-        eprintln!("Error: At least one feature must be enabled.");
-        std::process::exit(1);
+        println!("cargo:warning=No type feature is enabled, so there are no default MIME types.");
     }
 }
@@ -1,8 +1,119 @@
+use crate::response_cache::CachedResponse;
 use crate::{link_utils, FinderConfig};
 
 use async_stream::try_stream;
 use futures_core::Stream;
 
+/// Fetches a REST API response, replayed from or recorded to the `Fixtures` of the `FinderConfig` if any.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+/// * `url` - The URL to fetch.
+///
+/// # Returns
+///
+/// A `Result` containing the response, or an error if it cannot be fetched, replayed or recorded.
+async fn fetch(
+    client: &reqwest::Client,
+    config: &FinderConfig,
+    url: &str,
+) -> Result<CachedResponse, Box<dyn std::error::Error>> {
+    let fixtures = config.fixtures.as_ref();
+    if let Some(fixtures) = fixtures.filter(|fixtures| fixtures.is_replaying()) {
+        return fixtures
+            .response(url)
+            .ok_or(format!("No recorded response for {url}").into());
+    }
+    let fetched = fetch_cached(client, config, url).await?;
+    if let Some(fixtures) = fixtures {
+        fixtures.record_response(url, &fetched)?;
+    }
+    Ok(fetched)
+}
+
+/// Fetches a REST API response, through the `ResponseCache` of the `FinderConfig` if any.
+///
+/// Cached responses are revalidated with `If-None-Match` and `If-Modified-Since` headers and reused when the
+/// server answers 304 Not Modified. Offline, cached responses are reused without requests.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+/// * `url` - The URL to fetch.
+///
+/// # Returns
+///
+/// A `Result` containing the response, or an error if the request fails, if the response status is not
+/// successful, or if the response is not cached when offline.
+async fn fetch_cached(
+    client: &reqwest::Client,
+    config: &FinderConfig,
+    url: &str,
+) -> Result<CachedResponse, Box<dyn std::error::Error>> {
+    let cached = config
+        .response_cache
+        .as_ref()
+        .and_then(|cache| cache.get(url));
+    if config.offline {
+        return cached.ok_or(format!("No cached response for {url}").into());
+    }
+
+    let mut request = client.get(url);
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let response = link_utils::send_with_retry(request, &config.retry)
+        .await
+        .map_err(|e| format!("Failed to send request: {e}"))?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+    }
+    if !response.status().is_success() {
+        Err(format!(
+            "Failed to fetch URL, status code: {}",
+            response.status()
+        ))?;
+    }
+
+    let headers = response.headers();
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+    let next_link = link_utils::get_next_link_from_headers(headers);
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {e}"))?;
+
+    let fetched = CachedResponse {
+        etag,
+        last_modified,
+        next_link,
+        body,
+    };
+    if let Some(cache) = &config.response_cache {
+        cache.insert(url, &fetched)?;
+    }
+    Ok(fetched)
+}
+
 /// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It
 /// repeatedly makes GET requests to the API, following pagination links found in the response headers.
 ///
@@ -22,29 +133,76 @@ pub fn get_stream<'a>(
     let mut next_link = link_utils::build_url_from_config(config).ok();
     try_stream! {
         while let Some(url) = next_link {
-            let response = client.get(url).send().await.map_err(|e| {
-                format!("Failed to send request: {e}")
-            })?;
-
-            if !response.status().is_success() {
-                Err(format!("Failed to fetch URL, status code: {}", response.status()))?;
+            let response = fetch(client, config, &url).await?;
+            next_link = response.next_link;
+            let mut attachments_link = link_utils::build_attachments_url(config, &response.body);
+            yield response.body;
+            while let Some(url) = attachments_link {
+                let response = fetch(client, config, &url).await?;
+                attachments_link = response.next_link;
+                yield response.body;
             }
+        }
+    }
+}
 
-            let headers = response.headers();
-            next_link = link_utils::get_next_link_from_headers(headers);
-
-            let body = response.text().await.map_err(|e| {
-                format!("Failed to read response body: {e}")
-            })?;
+/// Post types registered by WordPress core that never hold public content.
+const INTERNAL_POST_TYPES: &[&str] = &[
+    "nav_menu_item",
+    "wp_block",
+    "wp_font_face",
+    "wp_font_family",
+    "wp_global_styles",
+    "wp_navigation",
+    "wp_template",
+    "wp_template_part",
+];
 
-            yield body;
-        }
+/// Fetches the post types exposed in the REST API by the `/wp/v2/types` endpoint.
+///
+/// Only post types served under the `wp/v2` namespace are kept, except the internal post types of WordPress core.
+/// Whether a post type is `viewable` is not known, the field being only returned in the `edit` context, which
+/// requires authentication.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// A `Result` containing the REST bases of the post types (e.g. `posts`, `pages`, `media`, `product`) if
+/// successful, or an error.
+pub async fn get_post_types(
+    client: &reqwest::Client,
+    config: &FinderConfig,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    if config.url.is_empty() {
+        return Err("URL is required".into());
     }
+
+    let response = fetch(
+        client,
+        config,
+        &format!("{}/wp-json/wp/v2/types", config.url),
+    )
+    .await?;
+
+    let types = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&response.body)
+        .map_err(|e| format!("Failed to read response body: {e}"))?;
+
+    Ok(types
+        .iter()
+        .filter(|(slug, _)| !INTERNAL_POST_TYPES.contains(&slug.as_str()))
+        .filter(|(_, value)| value["rest_namespace"].as_str().unwrap_or("wp/v2") == "wp/v2")
+        .filter_map(|(_, value)| value["rest_base"].as_str().map(str::to_string))
+        .collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::TempDir;
 
     use futures_util::pin_mut;
     use futures_util::StreamExt;
@@ -103,4 +261,153 @@ mod tests {
             .to_string()
             .starts_with("Failed to send request: error sending request"));
     }
+
+    #[tokio::test]
+    async fn test_get_stream_with_response_cache() {
+        let mut server = mockito::Server::new_async().await;
+
+        let fresh_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body("[1]")
+            .create_async()
+            .await;
+
+        let not_modified_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("api_cache");
+        let client = reqwest::Client::new();
+        let mut config = FinderConfig {
+            url: server.url(),
+            response_cache: Some(
+                crate::ResponseCache::open(&dir, std::time::Duration::from_secs(3600)).unwrap(),
+            ),
+            ..Default::default()
+        };
+
+        let mut bodies = vec![];
+        for offline in [false, false, true] {
+            config.offline = offline;
+            let stream = get_stream(&client, &config);
+            pin_mut!(stream);
+            bodies.push(stream.next().await.unwrap().unwrap());
+        }
+
+        fresh_mock.assert_async().await;
+        not_modified_mock.assert_async().await;
+        assert_eq!(bodies, vec!["[1]", "[1]", "[1]"]);
+
+        config.url = "http://example.com".to_string();
+        let stream = get_stream(&client, &config);
+        pin_mut!(stream);
+        assert_eq!(
+            stream.next().await.unwrap().unwrap_err().to_string(),
+            "No cached response for http://example.com/wp-json/wp/v2/media?per_page=100"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_parent_attachments() {
+        let mut server = mockito::Server::new_async().await;
+
+        let posts_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/pages?per_page=100&_embed=wp:featuredmedia",
+            )
+            .with_body(r#"[{"id":1},{"id":2}]"#)
+            .create_async()
+            .await;
+        let media_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100&parent=1,2")
+            .with_body(r#"[{"id":3,"post":1}]"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            target: crate::FinderTarget::Pages,
+            attachments: crate::FinderAttachments::Parent,
+            ..Default::default()
+        };
+        let bodies = get_stream(&client, &config)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        posts_mock.assert_async().await;
+        media_mock.assert_async().await;
+        assert_eq!(
+            bodies,
+            vec![r#"[{"id":1},{"id":2}]"#, r#"[{"id":3,"post":1}]"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_post_types() {
+        let mut server = mockito::Server::new_async().await;
+
+        let types = serde_json::json!({
+            "post": {"slug": "post", "rest_base": "posts", "rest_namespace": "wp/v2"},
+            "page": {"slug": "page", "rest_base": "pages", "rest_namespace": "wp/v2"},
+            "attachment": {"slug": "attachment", "rest_base": "media"},
+            "wp_block": {"slug": "wp_block", "rest_base": "blocks", "rest_namespace": "wp/v2"},
+            "product": {"slug": "product", "rest_base": "product", "rest_namespace": "wp/v2"},
+            "shop_order": {"slug": "shop_order", "rest_base": "orders", "rest_namespace": "wc/v3"}
+        });
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/types")
+            .with_body(types.to_string())
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let mut post_types = get_post_types(&client, &config).await.unwrap();
+        post_types.sort();
+
+        api_mock.assert_async().await;
+
+        assert_eq!(post_types, vec!["media", "pages", "posts", "product"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_post_types_with_404_api_url() {
+        let mut server = mockito::Server::new_async().await;
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/types")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let res = get_post_types(&client, &config).await;
+
+        api_mock.assert_async().await;
+
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "Failed to fetch URL, status code: 404 Not Found"
+        );
+    }
 }
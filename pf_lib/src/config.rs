@@ -1,6 +1,13 @@
+use crate::{
+    Extractors, FinderHooks, Fixtures, MimeRegistry, ProbeCache, ResponseCache, SeenUrls,
+    UploadBases, Verifiers,
+};
+
+use std::time::Duration;
+
 /// Represents the target type for the `Finder`.
 ///
-/// This enum is used to specify whether the `Finder` should target media or posts.
+/// This enum is used to specify whether the `Finder` should target media, posts, pages or a custom post type.
 /// The `Posts` variant includes fields to exclude specific categories and tags.
 #[derive(Default, PartialEq, Debug)]
 pub enum FinderTarget {
@@ -14,6 +21,148 @@ pub enum FinderTarget {
         /// Tags to exclude.
         tags_exclude: Vec<u16>,
     },
+    /// Target pages.
+    Pages,
+    /// Target items of a custom post type exposed in the REST API.
+    Custom {
+        /// The REST base of the post type (e.g. `product`).
+        rest_base: String,
+    },
+}
+
+impl FinderTarget {
+    /// Returns the REST base of the targeted resource.
+    pub fn rest_base(&self) -> &str {
+        match self {
+            FinderTarget::Media => "media",
+            FinderTarget::Posts { .. } => "posts",
+            FinderTarget::Pages => "pages",
+            FinderTarget::Custom { rest_base } => rest_base,
+        }
+    }
+}
+
+/// Represents where the `Finder` discovers content.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum FinderSource {
+    /// Pages of the WordPress REST API.
+    #[default]
+    Api,
+    /// Sitemaps (WordPress core, Yoast SEO and Rank Math sitemaps, including video and image entries), for
+    /// websites that disable the REST API. The `target` is ignored.
+    Sitemap,
+    /// HTML of the post pages listed in the sitemaps or in the feed, or of `?p=ID` links otherwise, including
+    /// `og:video`, `og:audio`, `twitter:player:stream` and JSON-LD `VideoObject` and `AudioObject` metadata. The
+    /// `target` is ignored.
+    Html,
+    /// RSS or Atom feed and category feeds, including enclosures and Media RSS contents, for websites that disable
+    /// the REST API. The `target` is ignored.
+    Feed,
+}
+
+/// Represents how the `Finder` discovers the attachments of posts, pages and custom post types, i.e. the media
+/// uploaded to them, which are otherwise only found when they are featured or linked in the content.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum FinderAttachments {
+    /// Attachments are not requested.
+    #[default]
+    Skip,
+    /// Attachments embedded in the pages of items (`_embed=wp:attachment`).
+    Embed,
+    /// Attachments of the items of each page, requested from the media endpoint with their IDs as parents
+    /// (`/wp/v2/media?parent=1,2,3`), for websites that limit embedding.
+    Parent,
+}
+
+/// Represents how the `Finder` confirms that a candidate URL is a file of a registered MIME type.
+#[derive(Default, PartialEq, Debug)]
+pub enum FinderVerification {
+    /// `Content-Type` header of a HEAD request, retried as a single byte range GET request when the server rejects
+    /// HEAD requests with a 403, 405 or 501 status.
+    #[default]
+    Head,
+    /// `Content-Type` header of a HEAD request, without GET fallback.
+    HeadOnly,
+    /// Magic bytes of the leading bytes fetched with a ranged GET request. Files whose content does not match their
+    /// `Content-Type` header or extension are reported as errors.
+    Sniff,
+}
+
+/// Hosts of placeholder files served instead of hotlinked or missing files.
+const PLACEHOLDER_HOSTS: &[&str] = &[
+    "dummyimage.com",
+    "placehold.co",
+    "placehold.it",
+    "placeholder.com",
+    "placekitten.com",
+];
+
+/// Represents how the `Finder` follows the redirects of candidate URLs.
+#[derive(PartialEq, Debug)]
+pub struct FinderRedirects {
+    /// Maximum number of redirects to follow.
+    pub max_hops: usize,
+    /// Whether to only follow redirects to the host of the candidate URL.
+    pub same_host_only: bool,
+    /// Hosts (and their subdomains) of placeholder files, whose final URLs are rejected.
+    pub placeholder_hosts: Vec<String>,
+}
+
+impl Default for FinderRedirects {
+    fn default() -> Self {
+        Self {
+            max_hops: 10,
+            same_host_only: false,
+            placeholder_hosts: PLACEHOLDER_HOSTS.iter().map(|h| h.to_string()).collect(),
+        }
+    }
+}
+
+/// Represents how the `Finder` retries the requests of its sources and its probe requests that fail transiently
+/// (connection errors, 429 and 5xx statuses).
+#[derive(PartialEq, Debug)]
+pub struct FinderRetry {
+    /// Maximum number of retries of a request.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following one.
+    pub backoff: Duration,
+}
+
+impl Default for FinderRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Represents how the `Html` source enumerates `?p=ID` links, when the website has neither sitemap nor feed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FinderEnumeration {
+    /// Highest ID enumerated.
+    pub max_id: u32,
+    /// Number of consecutive missing IDs after which enumeration stops.
+    pub max_missing_ids: u32,
+}
+
+impl Default for FinderEnumeration {
+    fn default() -> Self {
+        Self {
+            max_id: 1_000_000,
+            max_missing_ids: 100,
+        }
+    }
+}
+
+/// Represents which candidate URLs the `Finder` probes, so that low-value guesses are only probed when the budget
+/// of an item remains. Candidates are probed by decreasing confidence.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct FinderBudget {
+    /// Minimum confidence of the probed candidates, between 0 and 1 (e.g. 0.2 skips the `p3` slug guesses).
+    pub min_confidence: f32,
+    /// Maximum number of candidates probed per item, the likeliest ones, or `None` to probe them all.
+    pub max_guesses_per_item: Option<usize>,
 }
 
 /// Configuration for the `Finder`.
@@ -28,6 +177,71 @@ pub struct FinderConfig {
     /// The target type for the `Finder`.
     pub target: FinderTarget,
 
+    /// Where the `Finder` discovers content.
+    pub source: FinderSource,
+
+    /// How the `Finder` discovers the attachments of the targeted items, which only the `Api` source supports.
+    pub attachments: FinderAttachments,
+
+    /// The MIME types to look for, which default to the video types of the enabled features (cf.
+    /// `MimeRegistry::for_kind`).
+    pub mime_types: MimeRegistry,
+
+    /// How the `Finder` extracts candidate URLs from the items, which defaults to the built-in heuristics. Noisy
+    /// heuristics can be disabled with `Extractors::retain` (e.g. the `p3` slug guesses).
+    pub extractors: Extractors,
+
+    /// Which candidate URLs the `Finder` probes.
+    pub budget: FinderBudget,
+
+    /// How the `Finder` retries the requests of its sources and its probe requests.
+    pub retry: FinderRetry,
+
+    /// How the `Html` source enumerates `?p=ID` links.
+    pub enumeration: FinderEnumeration,
+
+    /// How the `Finder` confirms candidate URLs.
+    pub verification: FinderVerification,
+
+    /// The verifiers chained before the `verification`, which only confirms the candidate URLs they leave
+    /// undecided (e.g. an allow-list that rejects other hosts).
+    pub verifiers: Verifiers,
+
+    /// How the `Finder` follows the redirects of candidate URLs.
+    pub redirects: FinderRedirects,
+
+    /// The callbacks of the scan, e.g. to report its progress.
+    pub hooks: FinderHooks,
+
+    /// The candidate URLs already verified, which are skipped. The set is shared by the `find` calls of this
+    /// configuration, e.g. across targets.
+    pub seen: SeenUrls,
+
+    /// The candidate URLs that `find_candidates` listed as probed, which are shared by its calls of this
+    /// configuration like `seen`, e.g. so that a dry run across targets lists each URL as probed once.
+    pub listed: SeenUrls,
+
+    /// The upload folders learned from the URLs of media, which are shared by the `find` calls of this
+    /// configuration like `seen`, e.g. so that folders learned while scanning media are used to guess the files of
+    /// posts.
+    pub upload_bases: UploadBases,
+
+    /// The optional cache of the candidate URLs that turned out missing, which are not probed again until their
+    /// entry expires.
+    pub probe_cache: Option<ProbeCache>,
+
+    /// The optional cache of REST API responses, which are revalidated on later scans.
+    pub response_cache: Option<ResponseCache>,
+
+    /// Whether to scan without requests: REST API responses are replayed from the `response_cache`, and only the
+    /// candidate URLs they list as is (e.g. the `source_url` of an attachment) are trusted, unless the `probe_cache`
+    /// knows them as missing. Guessed URLs are left to `find_candidates`. Only the `Api` source supports it.
+    pub offline: bool,
+
+    /// The optional fixtures to record the REST API responses and probe results to, or to replay them from
+    /// without requests. Only the `Api` source supports replaying.
+    pub fixtures: Option<Fixtures>,
+
     /// Result set published before a given date (cf. <https://core.trac.wordpress.org/ticket/41032>).
     pub before: Option<String>,
 
@@ -0,0 +1,175 @@
+use crate::FinderConfig;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// Regular expression to match and capture the date and time parts of a W3C datetime string.
+    static ref W3C_DATE_RE: Regex =
+        Regex::new(r"^(\d{4}-\d{2}-\d{2})(?:[Tt ](\d{2}:\d{2})(:\d{2})?)?").unwrap();
+
+    /// Regular expression to match and capture the day, month, year and time parts of an RFC 2822 datetime string.
+    static ref RFC2822_DATE_RE: Regex =
+        Regex::new(r"^(?:[A-Za-z]{3},\s*)?(\d{1,2})\s+([A-Za-z]{3})\s+(\d{4})\s+(\d{2}:\d{2})(:\d{2})?").unwrap();
+}
+
+/// Abbreviated month names used by RFC 2822 datetimes.
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Normalizes a W3C datetime (e.g. a sitemap `lastmod`) to the `YYYY-MM-DDTHH:MM:SS` format of the REST API.
+///
+/// Fractional seconds and timezone designators are dropped, missing time parts default to midnight.
+///
+/// # Returns
+///
+/// An `Option` containing the normalized date, or `None` if the date cannot be parsed.
+pub fn normalize(date: &str) -> Option<String> {
+    let captures = W3C_DATE_RE.captures(date.trim())?;
+    Some(format!(
+        "{}T{}{}",
+        captures.get(1)?.as_str(),
+        captures.get(2).map_or("00:00", |m| m.as_str()),
+        captures.get(3).map_or(":00", |m| m.as_str())
+    ))
+}
+
+/// Normalizes an RFC 2822 datetime (e.g. an RSS `pubDate`) to the `YYYY-MM-DDTHH:MM:SS` format of the REST API.
+///
+/// The timezone is dropped, missing seconds default to zero.
+///
+/// # Returns
+///
+/// An `Option` containing the normalized date, or `None` if the date cannot be parsed.
+pub fn normalize_rfc2822(date: &str) -> Option<String> {
+    let captures = RFC2822_DATE_RE.captures(date.trim())?;
+    let month = MONTHS
+        .iter()
+        .position(|month| captures[2].eq_ignore_ascii_case(month))?;
+    Some(format!(
+        "{}-{:02}-{:0>2}T{}{}",
+        &captures[3],
+        month + 1,
+        &captures[1],
+        &captures[4],
+        captures.get(5).map_or(":00", |m| m.as_str())
+    ))
+}
+
+/// Checks whether a date is later than the `after` and `modified_after` bounds of the `FinderConfig`.
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+/// * `date` - A date normalized with `normalize`.
+///
+/// # Returns
+///
+/// `true` if the date is later than the bounds or if a bound cannot be parsed, `false` otherwise.
+pub fn is_after(config: &FinderConfig, date: &str) -> bool {
+    is_between(date, &config.after, &None) && is_between(date, &config.modified_after, &None)
+}
+
+/// Checks whether a date is strictly between two optional bounds, unparsable bounds being ignored.
+fn is_between(date: &str, after: &Option<String>, before: &Option<String>) -> bool {
+    after
+        .as_deref()
+        .and_then(normalize)
+        .is_none_or(|after| date > after.as_str())
+        && before
+            .as_deref()
+            .and_then(normalize)
+            .is_none_or(|before| date < before.as_str())
+}
+
+/// Checks whether an item falls within the date bounds of the `FinderConfig`.
+///
+/// Sources that only know the modification date of an item (e.g. sitemap `lastmod`) pass no publication date.
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+/// * `date` - The publication date normalized with `normalize`, if known.
+/// * `modified` - The modification date normalized with `normalize`, if known.
+///
+/// # Returns
+///
+/// `true` if the known dates are within bounds, `false` otherwise.
+pub fn is_in_range(config: &FinderConfig, date: Option<&str>, modified: Option<&str>) -> bool {
+    date.is_none_or(|date| is_between(date, &config.after, &config.before))
+        && modified.is_none_or(|modified| {
+            is_between(modified, &config.modified_after, &config.modified_before)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("2021-01-05T10:20:30+00:00"),
+            Some("2021-01-05T10:20:30".to_string())
+        );
+        assert_eq!(
+            normalize("2021-01-05T10:20Z"),
+            Some("2021-01-05T10:20:00".to_string())
+        );
+        assert_eq!(
+            normalize("2021-01-05"),
+            Some("2021-01-05T00:00:00".to_string())
+        );
+        assert_eq!(
+            normalize("2021-01-05t10:20:30.123"),
+            Some("2021-01-05T10:20:30".to_string())
+        );
+        assert_eq!(normalize("yesterday"), None);
+    }
+
+    #[test]
+    fn test_normalize_rfc2822() {
+        assert_eq!(
+            normalize_rfc2822("Tue, 05 Jan 2021 10:20:30 +0000"),
+            Some("2021-01-05T10:20:30".to_string())
+        );
+        assert_eq!(
+            normalize_rfc2822("5 Dec 2021 10:20 GMT"),
+            Some("2021-12-05T10:20:00".to_string())
+        );
+        assert_eq!(normalize_rfc2822("Tue, 05 Foo 2021 10:20:30 +0000"), None);
+        assert_eq!(normalize_rfc2822("2021-01-05T10:20:30"), None);
+    }
+
+    #[test]
+    fn test_is_after() {
+        let config = FinderConfig {
+            after: Some("2021-01-01T00:00:00".to_string()),
+            modified_after: Some("2021-06-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+
+        assert!(is_after(&config, "2021-07-01T00:00:00"));
+        assert!(!is_after(&config, "2021-03-01T00:00:00"));
+    }
+
+    #[test]
+    fn test_is_in_range() {
+        let config = FinderConfig {
+            after: Some("2021-01-01T00:00:00".to_string()),
+            before: Some("2022-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+
+        assert!(is_in_range(&config, Some("2021-06-01T00:00:00"), None));
+        assert!(!is_in_range(&config, Some("2020-06-01T00:00:00"), None));
+        assert!(!is_in_range(&config, Some("2022-06-01T00:00:00"), None));
+        assert!(is_in_range(&config, None, Some("2022-06-01T00:00:00")));
+        assert!(is_in_range(
+            &FinderConfig::default(),
+            Some("2022-06-01T00:00:00"),
+            None
+        ));
+    }
+}
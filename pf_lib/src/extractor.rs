@@ -0,0 +1,796 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::url_extractor::{unwrap_photon, UploadBase};
+use crate::MimeRegistry;
+
+lazy_static! {
+    /// Regular expression to match and capture base URL and slug of a complex URL.
+    static ref LINK_RE: Regex = Regex::new(r"^(https?://[^/]+)(?:/[^/]+)*/([^/]+)/?$").unwrap();
+
+    /// Regular expression to match the base URL of a URL.
+    static ref BASE_URL_RE: Regex = Regex::new(r"^https?://[^/?#]+").unwrap();
+
+    /// Regular expression to match a suffix WordPress appends to the name of an uploaded file.
+    static ref WP_SUFFIX_RE: Regex = Regex::new(r"-(?:\d+x\d+|scaled|e\d{13}|\d{1,2})$").unwrap();
+
+    /// Regular expression to match and capture month and year of a date string.
+    static ref DATE_RE: Regex = Regex::new(r"^(\d{4})-(\d{2})-\d{2}[tT]\d{2}:\d{2}:\d{2}$").unwrap();
+}
+
+/// What an `Extractor` knows about the website beyond the item it extracts URLs from.
+pub struct ExtractContext {
+    mime_types: MimeRegistry,
+    upload_bases: HashSet<UploadBase>,
+    content_re: Option<Regex>,
+}
+
+impl ExtractContext {
+    /// Creates the context of a page of items.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_types` - The MIME types to look for.
+    /// * `upload_bases` - The upload folders observed on the website.
+    pub fn new(mime_types: &MimeRegistry, upload_bases: &HashSet<UploadBase>) -> Self {
+        Self {
+            content_re: content_re(mime_types, upload_bases),
+            mime_types: mime_types.clone(),
+            upload_bases: upload_bases.clone(),
+        }
+    }
+
+    /// Returns the MIME types to look for.
+    pub fn mime_types(&self) -> &MimeRegistry {
+        &self.mime_types
+    }
+
+    /// Returns the upload folders observed on the website.
+    pub fn upload_bases(&self) -> &HashSet<UploadBase> {
+        &self.upload_bases
+    }
+
+    /// Returns the regular expression matching URLs of media files in HTML content, which captures the URL in its
+    /// first group, or `None` if there is no MIME type to look for.
+    pub fn content_re(&self) -> Option<&Regex> {
+        self.content_re.as_ref()
+    }
+}
+
+/// Builds the regular expression matching URLs of media files in the content body.
+///
+/// Any path prefix before `wp-content/uploads` is accepted (subdirectory installs, offloaded buckets,
+/// Jetpack Photon hosts), as well as multisite `sites/N` folders, non year/month layouts and any of the
+/// learned upload bases.
+///
+/// # Returns
+///
+/// An `Option` containing the regex, or `None` if there is no MIME type to look for.
+fn content_re(mime_types: &MimeRegistry, upload_bases: &HashSet<UploadBase>) -> Option<Regex> {
+    if mime_types.is_empty() {
+        return None;
+    }
+    let extensions = mime_types
+        .extensions()
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("|");
+    let upload_bases = std::iter::once(
+        r#"https?://[^/\s"'<>]+(?:/[^/\s"'<>]+)*?/wp-content/uploads/(?:sites/\d+/)?(?:\d{4}/\d{2}/)?[^/\s"'<>]+"#
+            .to_string(),
+    )
+    .chain(
+        upload_bases
+            .iter()
+            .map(|base| format!(r#"{}/[^\s"'<>]+?"#, regex::escape(&base.url))),
+    )
+    .collect::<Vec<_>>()
+    .join("|");
+    // URLs keep their case, but extensions are matched in any case (e.g. `.MP4`). The URL is captured, and must
+    // end at a delimiter rather than within a longer name (e.g. `.mp4x` or `.mp4.jpg`).
+    Regex::new(&format!(
+        r"((?:{upload_bases})\.(?i:{extensions}))(?:[^\w.\-/]|$)"
+    ))
+    .ok()
+}
+
+/// A candidate URL extracted from an item, to be verified.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// The URL.
+    pub url: String,
+    /// How likely the URL is to exist, from 0 (blind guess) to 1 (URL listed by WordPress).
+    pub confidence: f32,
+    /// Why the URL was extracted (e.g. `source_url`).
+    pub reason: String,
+}
+
+impl Candidate {
+    /// Creates a candidate.
+    pub fn new(url: impl Into<String>, confidence: f32, reason: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            confidence,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Extracts candidate URLs from the items of the REST API (or of the other sources, which mimic it).
+///
+/// Implementations can add site-specific extraction to the built-in heuristics, or replace them (cf.
+/// `Extractors`).
+pub trait Extractor: Send + Sync {
+    /// Returns the name of the extractor (e.g. `p1`).
+    fn name(&self) -> &str;
+
+    /// Extracts candidate URLs from an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The JSON item (e.g. a media, a post or a page).
+    /// * `context` - The `ExtractContext` of the website.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` containing the `Candidate`s, which are verified afterwards.
+    fn extract(&self, item: &serde_json::Value, context: &ExtractContext) -> Vec<Candidate>;
+}
+
+/// Returns the base names a WordPress upload may derive from, by stripping one by one the suffixes WordPress
+/// appends to file names: sizes (`-1024x576`), big image scaling (`-scaled`), edits (`-e` and a 13 digit
+/// timestamp) and deduplication (`-1`, `-2`).
+///
+/// # Arguments
+///
+/// * `name` - The file name, without extension.
+///
+/// # Returns
+///
+/// A `Vec` containing the name, then each of its normalized base names.
+fn base_names(name: &str) -> Vec<&str> {
+    let mut names = vec![name];
+    let mut name = name;
+    while let Some(m) = WP_SUFFIX_RE.find(name).filter(|m| m.start() > 0) {
+        name = &name[..m.start()];
+        names.push(name);
+    }
+    names
+}
+
+/// Heuristic `p1`: URLs of the files of media and of the featured media and attachments of posts (cf.
+/// `media_urls`).
+///
+/// URLs with a registered extension are kept as is, others are swapped to every registered extension (e.g. the
+/// poster image of a video), with a lower confidence. The resized, scaled, edited and deduplicated file names of
+/// the latter are normalized to their base names as well (cf. `base_names`), e.g. `clip-1024x576.jpg` to
+/// `clip.mp4`. The URLs of generated sizes are only normalized to their base names.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SourceUrlExtractor;
+
+impl Extractor for SourceUrlExtractor {
+    fn name(&self) -> &str {
+        "p1"
+    }
+
+    fn extract(&self, item: &serde_json::Value, context: &ExtractContext) -> Vec<Candidate> {
+        media_objects(item)
+            .flat_map(|(media, prefix)| {
+                media_urls(media).into_iter().map(move |(url, field)| {
+                    let size = field.starts_with("media_details.sizes.");
+                    (url, format!("{prefix}{field}"), size)
+                })
+            })
+            .flat_map(|(url, reason, size)| {
+                let Some((base_url, extension)) = url
+                    .rsplit_once('.')
+                    .filter(|(_, extension)| !extension.contains('/'))
+                else {
+                    return vec![];
+                };
+                if !size && context.mime_types().contains_extension(extension) {
+                    return vec![Candidate::new(url.as_str(), 1.0, reason.as_str())];
+                }
+                let (folder, name) = base_url.rsplit_once('/').unwrap_or(("", base_url));
+                let mut candidates = vec![];
+                // Generated sizes are thumbnails of the file, only their base names are worth guessing.
+                for (i, base_name) in base_names(name)
+                    .into_iter()
+                    .enumerate()
+                    .skip(usize::from(size))
+                {
+                    let (confidence, reason) = match i {
+                        0 => (0.5, reason.clone()),
+                        _ => (0.4, format!("{reason} named {base_name}")),
+                    };
+                    candidates.extend(context.mime_types().extensions().map(|ext| {
+                        Candidate::new(
+                            format!("{folder}/{base_name}.{ext}"),
+                            confidence,
+                            format!("{reason} with a .{ext} extension"),
+                        )
+                    }));
+                }
+                candidates
+            })
+            .collect()
+    }
+}
+
+/// Returns the media objects of an item: the item itself, its embedded featured media and its embedded
+/// attachments (`_embed=wp:attachment`), along with the prefix of their reasons.
+pub(crate) fn media_objects(
+    item: &serde_json::Value,
+) -> impl Iterator<Item = (&serde_json::Value, &'static str)> {
+    let embedded = |key: &str| {
+        // Embedded links are arrays of objects, or of arrays of objects when the link is a collection.
+        item["_embedded"][key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|value| match value.as_array() {
+                Some(array) => array.iter().collect::<Vec<_>>(),
+                None => vec![value],
+            })
+    };
+    std::iter::once((item, ""))
+        .chain(embedded("wp:featuredmedia").map(|media| (media, "featured media ")))
+        .chain(embedded("wp:attachment").map(|media| (media, "attachment ")))
+}
+
+/// Returns the URLs of the files of a media object, along with the field they come from: the `source_url`, the
+/// `guid` (often the original upload URL when the `source_url` is rewritten), the URL of each generated size and
+/// the original image of scaled images.
+fn media_urls(media: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(source_url) = media["source_url"].as_str() else {
+        return vec![];
+    };
+    let mut urls = vec![(unwrap_photon(source_url), "source_url".to_string())];
+    if let Some(guid) = media.pointer("/guid/rendered").and_then(|v| v.as_str()) {
+        urls.push((unwrap_photon(guid), "guid".to_string()));
+    }
+    if let Some(sizes) = media
+        .pointer("/media_details/sizes")
+        .and_then(|v| v.as_object())
+    {
+        urls.extend(sizes.iter().filter_map(|(size, details)| {
+            let url = details["source_url"].as_str()?;
+            Some((unwrap_photon(url), format!("media_details.sizes.{size}")))
+        }));
+    }
+    if let Some(original_image) = media
+        .pointer("/media_details/original_image")
+        .and_then(|v| v.as_str())
+    {
+        if let Some((folder, _)) = urls[0].0.rsplit_once('/') {
+            urls.push((
+                format!("{folder}/{original_image}"),
+                "media_details.original_image".to_string(),
+            ));
+        }
+    }
+    urls
+}
+
+/// Heuristic `p2`: URLs of media files in the `content.rendered` and `excerpt.rendered` fields of posts (cf.
+/// `ExtractContext::content_re`).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContentExtractor;
+
+impl Extractor for ContentExtractor {
+    fn name(&self) -> &str {
+        "p2"
+    }
+
+    fn extract(&self, item: &serde_json::Value, context: &ExtractContext) -> Vec<Candidate> {
+        let Some(content_re) = context.content_re() else {
+            return vec![];
+        };
+        ["content", "excerpt"]
+            .into_iter()
+            .filter_map(|field| {
+                let text = item.pointer(&format!("/{field}/rendered"))?.as_str()?;
+                Some((field, text))
+            })
+            .flat_map(|(field, text)| {
+                content_re
+                    .captures_iter(&text.replace('\\', "")) // Remove backslashes if necessary
+                    .map(|c| Candidate::new(unwrap_photon(&c[1]), 0.9, format!("{field}.rendered")))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Heuristic `p3`: URLs guessed from the `slug` field and the year and month of the `date` field of posts, and of
+/// their `modified` field with a window of months.
+///
+/// Files are looked up in the default `wp-content/uploads` folders as well as in every learned upload base. These
+/// blind guesses have the lowest confidence, the upload month of the `date` field being the likeliest.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SlugExtractor {
+    /// Number of months before and after the `date` and `modified` fields to look up, since files are often
+    /// uploaded before a post is published or updated. Without a window, only the month of the `date` field is
+    /// looked up.
+    pub months: u32,
+}
+
+impl SlugExtractor {
+    /// Returns the year and month folders to look up, with the confidence and reason of their guesses.
+    fn months(&self, item: &serde_json::Value) -> Vec<(String, String, f32, String)> {
+        let window = self.months as i32;
+        let mut offsets = (-window..=window).collect::<Vec<_>>();
+        offsets.sort_by_key(|offset| offset.abs());
+        let mut seen = HashSet::new();
+        let fields: &[&str] = match window {
+            0 => &["date"],
+            _ => &["date", "modified"],
+        };
+        fields
+            .iter()
+            .copied()
+            .filter_map(|field| {
+                let date = DATE_RE.captures(item[field].as_str()?)?;
+                let year = date[1].parse::<i32>().ok()?;
+                let month = date[2].parse::<i32>().ok()?;
+                Some((field, year * 12 + month - 1))
+            })
+            .flat_map(|(field, months)| offsets.iter().map(move |offset| (field, months, *offset)))
+            .filter(|(_, months, offset)| seen.insert(months + offset))
+            .map(|(field, months, offset)| {
+                let shifted = months + offset;
+                let (confidence, reason) = match offset {
+                    0 if field == "date" => (0.1, format!("slug and month of {field}")),
+                    0 => (0.05, format!("slug and month of {field}")),
+                    _ => (
+                        0.05,
+                        format!(
+                            "slug and {} month{} {} {field}",
+                            offset.abs(),
+                            if offset.abs() > 1 { "s" } else { "" },
+                            if offset < 0 { "before" } else { "after" },
+                        ),
+                    ),
+                };
+                (
+                    format!("{:04}", shifted.div_euclid(12)),
+                    format!("{:02}", shifted.rem_euclid(12) + 1),
+                    confidence,
+                    reason,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Extractor for SlugExtractor {
+    fn name(&self) -> &str {
+        "p3"
+    }
+
+    fn extract(&self, item: &serde_json::Value, context: &ExtractContext) -> Vec<Candidate> {
+        let Some(link) = item["link"].as_str() else {
+            return vec![];
+        };
+        // The slug of the link is a fallback for sources without a `slug` field, unless the link is not pretty
+        // (e.g. `?p=123`).
+        let slug = match item["slug"].as_str() {
+            Some(slug) if !slug.is_empty() => Some(slug),
+            _ => LINK_RE
+                .captures(link)
+                .and_then(|link| link.get(2))
+                .map(|slug| slug.as_str())
+                .filter(|slug| !slug.starts_with('?')),
+        };
+        let (Some(base_url), Some(slug)) = (BASE_URL_RE.find(link), slug) else {
+            return vec![];
+        };
+        let base_url = base_url.as_str();
+        let months = self.months(item);
+
+        let mut urls = HashSet::new();
+        [
+            format!("{base_url}/wp-content/uploads"),
+            format!("{base_url}/blog/wp-content/uploads"),
+        ]
+        .into_iter()
+        .map(|url| UploadBase { url, dated: true })
+        .chain(context.upload_bases().iter().cloned())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .flat_map(|upload_base| {
+            let upload_base = &upload_base;
+            months
+                .iter()
+                .flat_map(|(year, month, confidence, reason)| {
+                    context.mime_types().extensions().map(move |ext| {
+                        let url = upload_base.file_url(year, month, &format!("{slug}.{ext}"));
+                        Candidate::new(url, *confidence, reason)
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|candidate| urls.insert(candidate.url.clone()))
+        .collect()
+    }
+}
+
+/// Ordered list of the `Extractor`s of the `Finder`.
+///
+/// The default list holds the built-in heuristics `p1`, `p2` and `p3` (cf. `SourceUrlExtractor`,
+/// `ContentExtractor` and `SlugExtractor`). Clones share the same extractors.
+#[derive(Clone)]
+pub struct Extractors(Vec<Arc<dyn Extractor>>);
+
+impl Default for Extractors {
+    fn default() -> Self {
+        Self::empty()
+            .with(SourceUrlExtractor)
+            .with(ContentExtractor)
+            .with(SlugExtractor::default())
+    }
+}
+
+impl std::fmt::Debug for Extractors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl FromIterator<Arc<dyn Extractor>> for Extractors {
+    fn from_iter<I: IntoIterator<Item = Arc<dyn Extractor>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extractors {
+    /// Creates a list without any extractor.
+    pub fn empty() -> Self {
+        Self(vec![])
+    }
+
+    /// Appends an extractor.
+    pub fn with(mut self, extractor: impl Extractor + 'static) -> Self {
+        self.push(Arc::new(extractor));
+        self
+    }
+
+    /// Appends a shared extractor.
+    pub fn push(&mut self, extractor: Arc<dyn Extractor>) {
+        self.0.push(extractor);
+    }
+
+    /// Keeps the extractors for which a predicate holds (e.g. to disable the `p3` heuristic).
+    pub fn retain(&mut self, mut f: impl FnMut(&dyn Extractor) -> bool) {
+        self.0.retain(|extractor| f(extractor.as_ref()));
+    }
+
+    /// Returns an iterator over the extractors.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Extractor> {
+        self.0.iter().map(|extractor| extractor.as_ref())
+    }
+
+    /// Returns an iterator over the names of the extractors.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|extractor| extractor.name())
+    }
+
+    /// Returns the number of extractors.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether there is no extractor.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::AcfExtractor;
+
+    #[test]
+    fn test_extractors() {
+        let extractors = Extractors::default();
+        assert_eq!(extractors.names().collect::<Vec<_>>(), ["p1", "p2", "p3"]);
+
+        let extractors = extractors.with(AcfExtractor);
+        assert_eq!(extractors.len(), 4);
+        assert_eq!(format!("{extractors:?}"), r#"["p1", "p2", "p3", "acf"]"#);
+        assert!(Extractors::empty().is_empty());
+
+        let mut extractors = extractors;
+        extractors.retain(|extractor| extractor.name() != "p3");
+        assert_eq!(extractors.names().collect::<Vec<_>>(), ["p1", "p2", "acf"]);
+    }
+
+    #[test]
+    fn test_custom_extractor() {
+        let context = ExtractContext::new(&MimeRegistry::default(), &HashSet::new());
+        let item = json!({"acf": {"video_file": "http://example.com/media/video.mp4"}});
+
+        assert_eq!(
+            AcfExtractor.extract(&item, &context),
+            [Candidate::new(
+                "http://example.com/media/video.mp4",
+                1.0,
+                "acf.video_file"
+            )]
+        );
+        assert!(SourceUrlExtractor.extract(&item, &context).is_empty());
+    }
+
+    #[test]
+    fn test_base_names() {
+        assert_eq!(base_names("clip"), ["clip"]);
+        assert_eq!(base_names("clip-1024x576"), ["clip-1024x576", "clip"]);
+        assert_eq!(base_names("photo-scaled"), ["photo-scaled", "photo"]);
+        assert_eq!(
+            base_names("photo-e1612345678901"),
+            ["photo-e1612345678901", "photo"]
+        );
+        assert_eq!(base_names("clip-2"), ["clip-2", "clip"]);
+        assert_eq!(
+            base_names("clip-1-e1612345678901-scaled-300x169"),
+            [
+                "clip-1-e1612345678901-scaled-300x169",
+                "clip-1-e1612345678901-scaled",
+                "clip-1-e1612345678901",
+                "clip-1",
+                "clip"
+            ]
+        );
+        // Years and short timestamps are part of the name.
+        assert_eq!(base_names("recap-2021"), ["recap-2021"]);
+        assert_eq!(base_names("photo-e123"), ["photo-e123"]);
+        assert_eq!(base_names("-1"), ["-1"]);
+    }
+
+    #[test]
+    fn test_source_url_extractor_with_registered_extension() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4"), ("mov", "video/quicktime")]),
+            &HashSet::new(),
+        );
+        let item = json!({"source_url": "http://example.com/wp-content/uploads/2021/01/video.mov"});
+
+        // The URL is kept as is rather than swapped to another registered extension (e.g. `.mp4`).
+        assert_eq!(
+            SourceUrlExtractor.extract(&item, &context),
+            [Candidate::new(
+                "http://example.com/wp-content/uploads/2021/01/video.mov",
+                1.0,
+                "source_url"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_source_url_extractor_with_resized_poster() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            &HashSet::new(),
+        );
+        let item = json!({"source_url": "http://example.com/wp-content/uploads/2021/01/clip-1024x576.jpg"});
+
+        assert_eq!(
+            SourceUrlExtractor.extract(&item, &context),
+            [
+                Candidate::new(
+                    "http://example.com/wp-content/uploads/2021/01/clip-1024x576.mp4",
+                    0.5,
+                    "source_url with a .mp4 extension"
+                ),
+                Candidate::new(
+                    "http://example.com/wp-content/uploads/2021/01/clip.mp4",
+                    0.4,
+                    "source_url named clip with a .mp4 extension"
+                )
+            ]
+        );
+
+        // A resized file of a registered type is a file of its own, which is not normalized.
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4"), ("jpg", "image/jpeg")]),
+            &HashSet::new(),
+        );
+        assert_eq!(
+            SourceUrlExtractor.extract(&item, &context),
+            [Candidate::new(
+                "http://example.com/wp-content/uploads/2021/01/clip-1024x576.jpg",
+                1.0,
+                "source_url"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_source_url_extractor_with_sizes() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("jpg", "image/jpeg")]),
+            &HashSet::new(),
+        );
+        let item = json!({
+            "source_url": "http://example.com/wp-content/uploads/2021/01/photo-scaled.jpg",
+            "media_details": {"sizes": {
+                "medium": {"source_url": "http://example.com/wp-content/uploads/2021/01/photo-300x200.jpg"}
+            }}
+        });
+
+        // The thumbnail is not a candidate even though its extension is registered.
+        assert_eq!(
+            SourceUrlExtractor.extract(&item, &context),
+            [
+                Candidate::new(
+                    "http://example.com/wp-content/uploads/2021/01/photo-scaled.jpg",
+                    1.0,
+                    "source_url"
+                ),
+                Candidate::new(
+                    "http://example.com/wp-content/uploads/2021/01/photo.jpg",
+                    0.4,
+                    "media_details.sizes.medium named photo with a .jpg extension"
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_url_extractor_with_attachment_fields() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            &HashSet::new(),
+        );
+        let uploads = "https://example.com/wp-content/uploads/2021/01";
+        let item = json!({
+            "link": "https://example.com/post",
+            "guid": {"rendered": "https://example.com/?p=12"},
+            "_embedded": {"wp:attachment": [[{
+                "source_url": "https://cdn.example.com/2021/01/render.mp4",
+                "guid": {"rendered": format!("{uploads}/Master.mp4")},
+                "media_details": {
+                    "original_image": "poster.png",
+                    "sizes": {"thumbnail": {"source_url": format!("{uploads}/trailer-150x150.jpg")}}
+                }
+            }]]}
+        });
+
+        let candidates = SourceUrlExtractor.extract(&item, &context);
+        let reason = |url: &str| {
+            candidates
+                .iter()
+                .find(|candidate| candidate.url == url)
+                .map(|candidate| candidate.reason.as_str())
+        };
+
+        assert_eq!(
+            reason("https://cdn.example.com/2021/01/render.mp4"),
+            Some("attachment source_url")
+        );
+        assert_eq!(
+            reason(&format!("{uploads}/Master.mp4")),
+            Some("attachment guid")
+        );
+        assert_eq!(
+            reason(&format!("{uploads}/trailer.mp4")),
+            Some("attachment media_details.sizes.thumbnail named trailer with a .mp4 extension")
+        );
+        assert_eq!(reason(&format!("{uploads}/trailer-150x150.mp4")), None);
+        assert_eq!(
+            reason("https://cdn.example.com/2021/01/poster.mp4"),
+            Some("attachment media_details.original_image with a .mp4 extension")
+        );
+        // The guid of posts is not a file.
+        assert!(!candidates
+            .iter()
+            .any(|candidate| candidate.url.contains("?p=")));
+    }
+
+    #[test]
+    fn test_builtin_confidences() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            &HashSet::new(),
+        );
+        let item = json!({
+            "source_url": "http://example.com/wp-content/uploads/2021/01/poster.jpg",
+            "content": {"rendered": "<video src=\"http://example.com/wp-content/uploads/2021/01/a.mp4\">"},
+            "link": "http://example.com/post-slug",
+            "date": "2021-01-01t00:00:00"
+        });
+
+        assert_eq!(
+            SourceUrlExtractor.extract(&item, &context),
+            [Candidate::new(
+                "http://example.com/wp-content/uploads/2021/01/poster.mp4",
+                0.5,
+                "source_url with a .mp4 extension"
+            )]
+        );
+        assert_eq!(
+            ContentExtractor.extract(&item, &context),
+            [Candidate::new(
+                "http://example.com/wp-content/uploads/2021/01/a.mp4",
+                0.9,
+                "content.rendered"
+            )]
+        );
+        let guesses = SlugExtractor::default().extract(&item, &context);
+        assert_eq!(guesses.len(), 2);
+        assert!(guesses.iter().all(|candidate| candidate.confidence < 0.5));
+    }
+
+    #[test]
+    fn test_slug_extractor_with_slug_field() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            &HashSet::new(),
+        );
+        let item = json!({
+            "link": "http://example.com/?p=123",
+            "slug": "post-slug",
+            "date": "2021-01-01T00:00:00"
+        });
+        let urls = SlugExtractor::default()
+            .extract(&item, &context)
+            .into_iter()
+            .map(|candidate| candidate.url)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            urls,
+            HashSet::from([
+                "http://example.com/wp-content/uploads/2021/01/post-slug.mp4".to_string(),
+                "http://example.com/blog/wp-content/uploads/2021/01/post-slug.mp4".to_string(),
+            ])
+        );
+
+        let item = json!({"link": "http://example.com/?p=123", "date": "2021-01-01T00:00:00"});
+        assert!(SlugExtractor::default().extract(&item, &context).is_empty());
+    }
+
+    #[test]
+    fn test_slug_extractor_with_month_window() {
+        let context = ExtractContext::new(
+            &MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            &HashSet::from([UploadBase {
+                url: "http://example.com/files".to_string(),
+                dated: false,
+            }]),
+        );
+        let item = json!({
+            "link": "http://example.com/post-slug",
+            "date": "2021-01-15T00:00:00",
+            "modified": "2021-06-01T00:00:00"
+        });
+        let guesses = SlugExtractor { months: 1 }.extract(&item, &context);
+        let find = |url: &str| {
+            guesses
+                .iter()
+                .find(|candidate| candidate.url == url)
+                .unwrap_or_else(|| panic!("missing {url}"))
+        };
+
+        // 6 months in 2 dated folders, and the undated folder once.
+        assert_eq!(guesses.len(), 13);
+        let published = find("http://example.com/wp-content/uploads/2021/01/post-slug.mp4");
+        assert_eq!(published.confidence, 0.1);
+        assert_eq!(published.reason, "slug and month of date");
+        let uploaded = find("http://example.com/wp-content/uploads/2020/12/post-slug.mp4");
+        assert_eq!(uploaded.confidence, 0.05);
+        assert_eq!(uploaded.reason, "slug and 1 month before date");
+        assert_eq!(
+            find("http://example.com/wp-content/uploads/2021/06/post-slug.mp4").reason,
+            "slug and month of modified"
+        );
+        find("http://example.com/wp-content/uploads/2021/07/post-slug.mp4");
+        find("http://example.com/files/post-slug.mp4");
+        // The month of date in 2 dated folders, and the undated folder.
+        assert_eq!(SlugExtractor::default().extract(&item, &context).len(), 3);
+    }
+}
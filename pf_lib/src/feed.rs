@@ -0,0 +1,554 @@
+use crate::{date_utils, html, link_utils, sitemap, FinderConfig};
+
+use async_stream::try_stream;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+
+/// Feed locations of WordPress, RSS first. Both list the same posts, so the Atom feed is only read when there is
+/// no RSS feed.
+const FEED_PATHS: &[&str] = &["/feed/", "/feed/atom/"];
+
+/// Error of `get_items_stream` when the website has no feed.
+#[derive(Debug)]
+pub struct NoFeed;
+
+impl std::fmt::Display for NoFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "No feed found")
+    }
+}
+
+impl std::error::Error for NoFeed {}
+
+lazy_static! {
+    /// Regular expression to match and capture the content of RSS `<item>` entries.
+    static ref ITEM_RE: Regex = Regex::new(r"(?s)<item>(.*?)</item>").unwrap();
+
+    /// Regular expression to match and capture the content of Atom `<entry>` entries.
+    static ref ENTRY_RE: Regex = Regex::new(r"(?s)<entry(?:\s[^>]*)?>(.*?)</entry>").unwrap();
+
+    /// Regular expression to match and capture the `<link>` of an RSS item.
+    static ref LINK_RE: Regex = Regex::new(r"(?s)<link>(.*?)</link>").unwrap();
+
+    /// Regular expression to match and capture the `<pubDate>` of an RSS item.
+    static ref PUB_DATE_RE: Regex = Regex::new(r"(?s)<pubDate>(.*?)</pubDate>").unwrap();
+
+    /// Regular expression to match and capture the `<content:encoded>` of an RSS item.
+    static ref CONTENT_ENCODED_RE: Regex = Regex::new(r"(?s)<content:encoded>(.*?)</content:encoded>").unwrap();
+
+    /// Regular expression to match and capture the `<description>` of an RSS item.
+    static ref DESCRIPTION_RE: Regex = Regex::new(r"(?s)<description>(.*?)</description>").unwrap();
+
+    /// Regular expression to match RSS `<enclosure>` and Media RSS `<media:content>` elements.
+    static ref ENCLOSURE_RE: Regex = Regex::new(r"(?s)<(?:enclosure|media:content)\s[^>]*>").unwrap();
+
+    /// Regular expression to match Atom `<link>` elements.
+    static ref ATOM_LINK_RE: Regex = Regex::new(r"(?s)<link\s[^>]*>").unwrap();
+
+    /// Regular expression to match and capture the `<published>` of an Atom entry.
+    static ref PUBLISHED_RE: Regex = Regex::new(r"(?s)<published>(.*?)</published>").unwrap();
+
+    /// Regular expression to match and capture the `<updated>` of an Atom entry.
+    static ref UPDATED_RE: Regex = Regex::new(r"(?s)<updated>(.*?)</updated>").unwrap();
+
+    /// Regular expression to match and capture the `<content>` of an Atom entry.
+    static ref ATOM_CONTENT_RE: Regex = Regex::new(r"(?s)<content(?:\s[^>]*)?>(.*?)</content>").unwrap();
+
+    /// Regular expression to match and capture the `<summary>` of an Atom entry.
+    static ref SUMMARY_RE: Regex = Regex::new(r"(?s)<summary(?:\s[^>]*)?>(.*?)</summary>").unwrap();
+}
+
+/// Captures and decodes the first match of `re` in `text`.
+fn capture(re: &Regex, text: &str) -> Option<String> {
+    re.captures(text)
+        .map(|captures| sitemap::decode(&captures[1]))
+}
+
+/// Returns the value of the attribute `name` of an XML tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    html::attributes(tag).find_map(|(key, value)| (key == name).then_some(value))
+}
+
+/// Converts an RSS `<item>` or an Atom `<entry>` to items shaped like WordPress REST API items.
+///
+/// The entry becomes a post item (`link`, `date`, `modified`, `content` and `excerpt`), while its enclosures and
+/// Media RSS contents become media items (`source_url`).
+fn parse_entry(entry: &str, is_atom: bool) -> Vec<serde_json::Value> {
+    let (post, media) = if is_atom {
+        let links = ATOM_LINK_RE
+            .find_iter(entry)
+            .map(|tag| {
+                (
+                    attribute(tag.as_str(), "rel").unwrap_or_else(|| "alternate".to_string()),
+                    attribute(tag.as_str(), "href"),
+                )
+            })
+            .collect::<Vec<_>>();
+        let find_links = |rel: &'static str| {
+            links
+                .iter()
+                .filter(move |(r, _)| r == rel)
+                .filter_map(|(_, href)| href.clone())
+        };
+        (
+            serde_json::json!({
+                "link": find_links("alternate").next(),
+                "date": capture(&PUBLISHED_RE, entry).as_deref().and_then(date_utils::normalize),
+                "modified": capture(&UPDATED_RE, entry).as_deref().and_then(date_utils::normalize),
+                "content": {"rendered": capture(&ATOM_CONTENT_RE, entry)},
+                "excerpt": {"rendered": capture(&SUMMARY_RE, entry)},
+            }),
+            find_links("enclosure").collect::<Vec<_>>(),
+        )
+    } else {
+        (
+            serde_json::json!({
+                "link": capture(&LINK_RE, entry),
+                "date": capture(&PUB_DATE_RE, entry).as_deref().and_then(date_utils::normalize_rfc2822),
+                "modified": null,
+                "content": {"rendered": capture(&CONTENT_ENCODED_RE, entry)},
+                "excerpt": {"rendered": capture(&DESCRIPTION_RE, entry)},
+            }),
+            ENCLOSURE_RE
+                .find_iter(entry)
+                .filter_map(|tag| attribute(tag.as_str(), "url"))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    std::iter::once(post)
+        .chain(
+            media
+                .into_iter()
+                .map(|url| serde_json::json!({ "source_url": url })),
+        )
+        .collect()
+}
+
+/// Parses an RSS or Atom feed page.
+///
+/// # Returns
+///
+/// A `Vec` containing, for each entry, its items (see `parse_entry`).
+fn parse_feed(xml: &str) -> Vec<Vec<serde_json::Value>> {
+    let is_atom = !ITEM_RE.is_match(xml);
+    let re: &Regex = if is_atom { &ENTRY_RE } else { &ITEM_RE };
+    re.captures_iter(xml)
+        .map(|captures| parse_entry(&captures[1], is_atom))
+        .collect()
+}
+
+/// Fetches the slugs of the non-empty categories of a website from the REST API, following its pagination.
+///
+/// # Returns
+///
+/// The slugs of the categories, or the ones fetched so far when the REST API is not available.
+async fn get_category_slugs(client: &reqwest::Client, config: &FinderConfig) -> Vec<String> {
+    let mut slugs = vec![];
+    let mut next = Some(format!(
+        "{}/wp-json/wp/v2/categories?per_page=100&hide_empty=true&_fields=slug",
+        config.url
+    ));
+    while let Some(url) = next {
+        let Ok(response) = link_utils::send_with_retry(client.get(&url), &config.retry).await
+        else {
+            break;
+        };
+        if !response.status().is_success() {
+            break;
+        }
+        next = link_utils::get_next_link_from_headers(response.headers());
+        let Ok(categories) = response.json::<Vec<serde_json::Value>>().await else {
+            break;
+        };
+        slugs.extend(
+            categories
+                .iter()
+                .filter_map(|category| category["slug"].as_str())
+                .map(str::to_string),
+        );
+    }
+    slugs
+}
+
+/// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It
+/// paginates the RSS feed (or the Atom feed when there is no RSS feed), then the feeds of the categories listed by
+/// the REST API, with `?paged=N` and yields, for each feed page, its entries not yielded yet as items shaped like
+/// WordPress REST API items. Entries published out of the `FinderConfig` date bounds are skipped, and pagination
+/// stops once entries are older than the `after` bound.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// An asynchronous `futures_core::Stream` of `Result` containing the items of a feed page if successful, or an
+/// error.
+pub fn get_items_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+) -> impl Stream<Item = Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>> + 'a {
+    try_stream! {
+        if config.url.is_empty() {
+            Err("URL is required")?;
+        }
+
+        let mut found = false;
+        let mut categories_listed = false;
+        let mut yielded_links = HashSet::new();
+
+        let mut paths = FEED_PATHS.iter().map(|path| path.to_string()).collect::<VecDeque<_>>();
+        loop {
+            // Category feeds also list the posts the main feed leaves out (e.g. categories excluded by a plugin).
+            let Some(path) = paths.pop_front() else {
+                if categories_listed {
+                    break;
+                }
+                categories_listed = true;
+                let slugs = get_category_slugs(client, config).await;
+                paths.extend(slugs.iter().map(|slug| format!("/category/{slug}/feed/")));
+                continue;
+            };
+
+            let mut page = 1;
+            let mut previous_links = vec![];
+            loop {
+                let url = match page {
+                    1 => format!("{}{path}", config.url),
+                    _ => format!("{}{path}?paged={page}", config.url),
+                };
+
+                let response = link_utils::send_with_retry(client.get(&url), &config.retry)
+                    .await
+                    .map_err(|e| format!("Failed to send request: {e}"))?;
+
+                // WordPress answers 404 past the last page.
+                if !response.status().is_success() {
+                    break;
+                }
+
+                let xml = response.text().await.map_err(|e| {
+                    format!("Failed to read response body: {e}")
+                })?;
+
+                let entries = parse_feed(&xml);
+                let links = entries.iter().map(|items| items[0]["link"].clone()).collect::<Vec<_>>();
+                if entries.is_empty() || links == previous_links {
+                    break;
+                }
+                found = true;
+
+                // Feeds are sorted by publication date, newest first.
+                let is_last_page = config.after.as_deref().and_then(date_utils::normalize).is_some_and(|after| {
+                    entries.iter().all(|items| {
+                        items[0]["date"].as_str().is_some_and(|date| date <= after.as_str())
+                    })
+                });
+
+                let items = entries
+                    .into_iter()
+                    .filter(|items| {
+                        date_utils::is_in_range(config, items[0]["date"].as_str(), items[0]["modified"].as_str())
+                            && yielded_links.insert(items[0]["link"].clone())
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+                if !items.is_empty() {
+                    yield items;
+                }
+
+                if is_last_page {
+                    break;
+                }
+                previous_links = links;
+                page += 1;
+            }
+
+            // Both main feeds list the same posts.
+            if found && !categories_listed {
+                paths.clear();
+            }
+        }
+
+        if !found {
+            Err(NoFeed)?;
+        }
+    }
+}
+
+/// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It yields,
+/// for each feed page, its entries as a JSON array of items shaped like WordPress REST API items, so that they can
+/// go through the same extraction as `api::get_stream` pages.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// An asynchronous `futures_core::Stream` of `Result` containing a JSON array `String` if successful, or an
+/// error.
+pub fn get_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+) -> impl Stream<Item = Result<String, Box<dyn std::error::Error>>> + 'a {
+    get_items_stream(client, config)
+        .map(|items| items.map(|items| serde_json::Value::Array(items).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::pin_mut;
+
+    #[test]
+    fn test_parse_feed_with_rss() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:media="http://search.yahoo.com/mrss/">
+            <channel>
+                <link>http://example.com</link>
+                <item>
+                    <link>http://example.com/hello-world/</link>
+                    <pubDate>Tue, 05 Jan 2021 10:00:00 +0000</pubDate>
+                    <description><![CDATA[<p>Hello</p>]]></description>
+                    <content:encoded><![CDATA[<video src="http://example.com/wp-content/uploads/2021/01/a.mp4"></video>]]></content:encoded>
+                    <enclosure url="http://example.com/wp-content/uploads/2021/01/b.mp4" length="1" type="video/mp4" />
+                    <media:content url="http://cdn.example.com/c.mov" medium="video" />
+                </item>
+            </channel>
+            </rss>"#;
+
+        assert_eq!(
+            parse_feed(xml),
+            vec![vec![
+                serde_json::json!({
+                    "link": "http://example.com/hello-world/",
+                    "date": "2021-01-05T10:00:00",
+                    "modified": null,
+                    "content": {"rendered": "<video src=\"http://example.com/wp-content/uploads/2021/01/a.mp4\"></video>"},
+                    "excerpt": {"rendered": "<p>Hello</p>"},
+                }),
+                serde_json::json!({"source_url": "http://example.com/wp-content/uploads/2021/01/b.mp4"}),
+                serde_json::json!({"source_url": "http://cdn.example.com/c.mov"}),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_parse_feed_with_atom() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <link rel="alternate" type="text/html" href="http://example.com" />
+                <entry>
+                    <link rel="alternate" type="text/html" href="http://example.com/hello-world/" />
+                    <link rel="enclosure" type="video/mp4" length="1" href="http://example.com/wp-content/uploads/2021/01/b.mp4" />
+                    <published>2021-01-05T10:00:00Z</published>
+                    <updated>2021-02-05T10:00:00Z</updated>
+                    <summary type="html"><![CDATA[Hello]]></summary>
+                    <content type="html">&lt;p&gt;Hello&lt;/p&gt;</content>
+                </entry>
+            </feed>"#;
+
+        assert_eq!(
+            parse_feed(xml),
+            vec![vec![
+                serde_json::json!({
+                    "link": "http://example.com/hello-world/",
+                    "date": "2021-01-05T10:00:00",
+                    "modified": "2021-02-05T10:00:00",
+                    "content": {"rendered": "<p>Hello</p>"},
+                    "excerpt": {"rendered": "Hello"},
+                }),
+                serde_json::json!({"source_url": "http://example.com/wp-content/uploads/2021/01/b.mp4"}),
+            ]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_items_stream() {
+        let mut server = mockito::Server::new_async().await;
+
+        let item = |link: &str, date: &str| {
+            format!("<item><link>http://example.com/{link}/</link><pubDate>{date}</pubDate></item>")
+        };
+
+        let page_1_mock = server
+            .mock("GET", "/feed/")
+            .with_body(format!(
+                "<rss><channel>{}{}</channel></rss>",
+                item("newest", "Mon, 01 Mar 2021 00:00:00 +0000"),
+                item("new", "Mon, 01 Feb 2021 00:00:00 +0000")
+            ))
+            .create_async()
+            .await;
+
+        let page_2_mock = server
+            .mock("GET", "/feed/?paged=2")
+            .with_body(format!(
+                "<rss><channel>{}{}</channel></rss>",
+                item("recent", "Fri, 15 Jan 2021 00:00:00 +0000"),
+                item("old", "Tue, 01 Dec 2020 00:00:00 +0000")
+            ))
+            .create_async()
+            .await;
+
+        let page_3_mock = server
+            .mock("GET", "/feed/?paged=3")
+            .with_body(format!(
+                "<rss><channel>{}</channel></rss>",
+                item("older", "Sun, 01 Nov 2020 00:00:00 +0000")
+            ))
+            .create_async()
+            .await;
+
+        let page_4_mock = server
+            .mock("GET", "/feed/?paged=4")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            after: Some("2021-01-01T00:00:00".to_string()),
+            before: Some("2021-03-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+
+        let stream = get_items_stream(&client, &config);
+        pin_mut!(stream);
+
+        let links = stream
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await
+            .concat()
+            .into_iter()
+            .map(|item| item["link"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        page_1_mock.assert_async().await;
+        page_2_mock.assert_async().await;
+        page_3_mock.assert_async().await;
+        page_4_mock.assert_async().await;
+
+        assert_eq!(
+            links,
+            vec!["http://example.com/new/", "http://example.com/recent/"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_items_stream_with_category_feeds() {
+        let mut server = mockito::Server::new_async().await;
+
+        let feed = |links: &[&str]| {
+            let items = links
+                .iter()
+                .map(|link| format!("<item><link>http://example.com/{link}/</link></item>"))
+                .collect::<String>();
+            format!("<rss><channel>{items}</channel></rss>")
+        };
+
+        let feed_mock = server
+            .mock("GET", "/feed/")
+            .with_body(feed(&["first", "second"]))
+            .create_async()
+            .await;
+        let atom_mock = server
+            .mock("GET", "/feed/atom/")
+            .expect(0)
+            .create_async()
+            .await;
+        let categories_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/categories?per_page=100&hide_empty=true&_fields=slug",
+            )
+            .with_body(r#"[{"slug": "news"}, {"slug": "videos"}]"#)
+            .create_async()
+            .await;
+        let news_mock = server
+            .mock("GET", "/category/news/feed/")
+            .with_body(feed(&["second"]))
+            .create_async()
+            .await;
+        let videos_mock = server
+            .mock("GET", "/category/videos/feed/")
+            .with_body(feed(&["hidden"]))
+            .create_async()
+            .await;
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Regex(r"paged=2$".to_string()))
+            .with_status(404)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let links = get_items_stream(&client, &config)
+            .map(|items| items.unwrap())
+            .collect::<Vec<_>>()
+            .await
+            .concat()
+            .into_iter()
+            .map(|item| item["link"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        feed_mock.assert_async().await;
+        atom_mock.assert_async().await;
+        categories_mock.assert_async().await;
+        news_mock.assert_async().await;
+        videos_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+
+        // Posts listed by several feeds are only yielded once.
+        assert_eq!(
+            links,
+            vec![
+                "http://example.com/first/",
+                "http://example.com/second/",
+                "http://example.com/hidden/"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_items_stream_with_no_feed() {
+        let mut server = mockito::Server::new_async().await;
+
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(404)
+            .expect(FEED_PATHS.len() + 1)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let stream = get_items_stream(&client, &config);
+        pin_mut!(stream);
+
+        let next = stream.next().await.unwrap();
+
+        not_found_mock.assert_async().await;
+
+        assert!(next.err().unwrap().is::<NoFeed>());
+    }
+}
@@ -1,16 +1,95 @@
 use crate::api;
+use crate::feed;
+use crate::html;
 use crate::link_utils;
+use crate::sitemap;
 use crate::url_extractor;
-use crate::FinderConfig;
+use crate::verifier::{HeadVerifier, SniffVerifier, Verdict, VerifyContext};
+use crate::{
+    Extractor, Extractors, FinderConfig, FinderHooks, FinderMatch, FinderProbe, FinderRetry,
+    FinderSource, FinderVerification, Fixtures, Verifier,
+};
 
-use async_stream::try_stream;
+use async_stream::stream;
 use futures_core::Stream;
 use futures_util::pin_mut;
+use futures_util::stream::LocalBoxStream;
 use futures_util::StreamExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Minimum confidence of the candidate URLs whose extension case variants are probed when they are rejected, i.e.
+/// URLs found as is on the website (e.g. `p1` source URLs and `p2` content links) rather than guessed, so that the
+/// variants do not multiply the probes of low-value guesses beyond the `FinderBudget`.
+const MIN_CASE_VARIANTS_CONFIDENCE: f32 = 0.9;
+
+/// Minimum confidence of the candidate URLs trusted by offline scans, i.e. URLs that the cached REST API responses
+/// list as is (e.g. the `source_url` of an attachment), rather than links of a post content or guesses.
+const MIN_OFFLINE_CONFIDENCE: f32 = 1.0;
+
+/// Returns the stream of JSON pages of a `FinderSource`.
+fn get_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+    source: FinderSource,
+) -> LocalBoxStream<'a, Result<String, Box<dyn std::error::Error>>> {
+    if config.offline && source != FinderSource::Api {
+        let error = "Offline scans only support the REST API source";
+        return futures_util::stream::once(async move { Err(error.into()) }).boxed_local();
+    }
+    let replaying = config.fixtures.as_ref().is_some_and(Fixtures::is_replaying);
+    if replaying && source != FinderSource::Api {
+        let error = "Replayed scans only support the REST API source";
+        return futures_util::stream::once(async move { Err(error.into()) }).boxed_local();
+    }
+    match source {
+        FinderSource::Api => api::get_stream(client, config).boxed_local(),
+        FinderSource::Sitemap => sitemap::get_stream(client, config).boxed_local(),
+        FinderSource::Html => html::get_stream(client, config).boxed_local(),
+        FinderSource::Feed => feed::get_stream(client, config).boxed_local(),
+    }
+}
+
+/// A candidate URL listed by `find_candidates`, without probing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinderCandidate {
+    /// The URL.
+    pub url: String,
+    /// The confidence of the extractor, between 0 and 1.
+    pub confidence: f32,
+    /// Why the extractor proposed the URL (e.g. `content.rendered`).
+    pub reason: String,
+    /// The name of the extractor (e.g. `p2`).
+    pub heuristic: String,
+    /// The ID of the item the URL was extracted from, which sources other than the REST API do not know.
+    pub item_id: Option<u64>,
+    /// Whether `find_matches` would probe the URL, which it does not when the URL is beyond the budget (cf.
+    /// `FinderBudget`), already seen, listed before (cf. `FinderConfig::listed`), known as missing by the
+    /// `ProbeCache` or, offline, not trusted (cf. `FinderConfig::offline`).
+    pub probed: bool,
+}
+
+impl FinderCandidate {
+    /// Converts the candidate to a JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "url": self.url,
+            "confidence": self.confidence,
+            "reason": self.reason,
+            "heuristic": self.heuristic,
+            "item_id": self.item_id,
+            "probed": self.probed,
+        })
+    }
+}
 
 /// Creates an asynchronous stream that fetches video URLs based on the provided configuration.
 ///
-/// This function fetches new URLs from the WordPress API. It ensures that the video URLs exist before returning them.
+/// This function fetches new URLs from the WordPress API, the sitemaps, the HTML pages or the feed depending on
+/// the `FinderSource`. It ensures that the video URLs exist before returning them, as configured by the
+/// `FinderVerification`.
+/// Upload folders observed on a page are remembered and used to extract URLs from the following pages, and URLs
+/// already verified (cf. `FinderConfig::seen`) are skipped.
 ///
 /// # Arguments
 ///
@@ -48,28 +127,532 @@ use futures_util::StreamExt;
 pub fn find(
     config: &FinderConfig,
 ) -> impl Stream<Item = Result<String, Box<dyn std::error::Error>>> + '_ {
+    find_matches(config).map(|res| res.map(|found| found.url))
+}
+
+/// Creates an asynchronous stream that fetches video URLs based on the provided configuration, like `find`, along
+/// with their MIME type and how they were confirmed.
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// A `futures_core::Stream` over the `FinderMatch` of existing video URLs.
+pub fn find_matches(
+    config: &FinderConfig,
+) -> impl Stream<Item = Result<FinderMatch, Box<dyn std::error::Error>>> + '_ {
+    let client = reqwest::Client::new();
+    stream! {
+        let probe_client = match link_utils::probe_client() {
+            Ok(probe_client) => probe_client,
+            Err(e) => {
+                yield Err(e);
+                return;
+            }
+        };
+        let stream = matches(&client, &probe_client, config, config.source, 1);
+        pin_mut!(stream);
+        while let Some(found) = stream.next().await {
+            yield found;
+        }
+    }
+}
+
+/// Creates an asynchronous stream that lists the candidate URLs extracted from the items, like a dry run of
+/// `find_matches`.
+///
+/// Every candidate of every extractor is listed, along with whether it would be probed. Candidate URLs are
+/// neither probed nor marked as seen, only the requests of the source are sent.
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// A `futures_core::Stream` over the `FinderCandidate`s.
+pub fn find_candidates(
+    config: &FinderConfig,
+) -> impl Stream<Item = Result<FinderCandidate, Box<dyn std::error::Error>>> + '_ {
     let client = reqwest::Client::new();
-    try_stream! {
-        let stream = api::get_stream(&client, config);
+    stream! {
+        let stream = candidates(&client, config, config.source);
+        pin_mut!(stream);
+        while let Some(candidate) = stream.next().await {
+            yield candidate;
+        }
+    }
+}
+
+/// Extracts the candidate URLs of a page, learning its upload folders for the following pages and targets.
+fn xtract(body: &str, config: &FinderConfig) -> url_extractor::Xtract {
+    let xtract = url_extractor::Xtract::new(body)
+        .with_upload_bases(&config.upload_bases.snapshot())
+        .with_mime_types(&config.mime_types)
+        .with_extractors(&config.extractors)
+        .with_budget(&config.budget);
+    config.upload_bases.extend(xtract.upload_bases());
+    xtract
+}
+
+/// Creates the stream of `find_candidates` for a source.
+fn candidates<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+    source: FinderSource,
+) -> impl Stream<Item = Result<FinderCandidate, Box<dyn std::error::Error>>> + 'a {
+    stream! {
+        let stream = get_stream(client, config, source);
+        pin_mut!(stream);
+        while let Some(body) = stream.next().await {
+            let body = match body {
+                Ok(body) => body,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+            let xtract = xtract(&body, config);
+            let budgeted = xtract
+                .candidates()
+                .into_iter()
+                .map(|candidate| candidate.url)
+                .collect::<HashSet<_>>();
+            for (item, heuristic, candidate) in xtract.listing() {
+                let cached = config
+                    .probe_cache
+                    .as_ref()
+                    .is_some_and(|cache| cache.get(&candidate.url).is_some());
+                let untrusted = config.offline && candidate.confidence < MIN_OFFLINE_CONFIDENCE;
+                let probed = budgeted.contains(&candidate.url)
+                    && !config.seen.contains(&candidate.url)
+                    && !cached
+                    && !untrusted
+                    && config.listed.mark(&candidate.url);
+                yield Ok(FinderCandidate {
+                    url: candidate.url,
+                    confidence: candidate.confidence,
+                    reason: candidate.reason,
+                    heuristic: heuristic.to_string(),
+                    item_id: item["id"].as_u64(),
+                    probed,
+                });
+            }
+        }
+    }
+}
+
+/// Creates the stream of `find_matches` for a source, probing up to `concurrency` candidate URLs at once.
+fn matches<'a>(
+    client: &'a reqwest::Client,
+    probe_client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+    source: FinderSource,
+    concurrency: usize,
+) -> impl Stream<Item = Result<FinderMatch, Box<dyn std::error::Error>>> + 'a {
+    stream! {
+        let stream = get_stream(client, config, source);
         pin_mut!(stream);
         while let Some(body) = stream.next().await {
-            for url in url_extractor::Xtract::new(&body?).run() {
-                if link_utils::does_link_exist(&client, &url).await {
-                    yield url;
+            let body = match body {
+                Ok(body) => body,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+            let xtract = xtract(&body, config);
+            let mut urls = vec![];
+            // Likelier candidates are probed first.
+            // URLs are persisted as seen once verified (cf. `verify`).
+            for candidate in xtract.candidates() {
+                if config.seen.mark(&candidate.url) {
+                    urls.push((candidate.url, candidate.confidence));
+                }
+            }
+            let probes = futures_util::stream::iter(urls)
+                .map(|(url, confidence)| async move {
+                    let probed = probe(probe_client, config, &url, confidence).await;
+                    (url, probed)
+                })
+                .buffer_unordered(concurrency.max(1));
+            pin_mut!(probes);
+            while let Some((url, probed)) = probes.next().await {
+                let fixtures = config.fixtures.as_ref();
+                if let Some(fixtures) = fixtures.filter(|fixtures| !fixtures.is_replaying()) {
+                    if let Err(e) = fixtures.record_probe(&url, &probed) {
+                        yield Err(e);
+                        return;
+                    }
+                }
+                // Mismatches are reported without ending the stream.
+                match probed {
+                    Ok(Some(found)) => {
+                        config.hooks.matched(&found);
+                        yield Ok(found);
+                    }
+                    Ok(None) => {}
+                    Err(e) => yield Err(e),
+                }
+            }
+        }
+    }
+}
+
+/// Confirms a candidate URL, then the case variants of its extension if it is rejected (e.g. `.MP4` on
+/// case-sensitive servers) and likely enough (cf. `MIN_CASE_VARIANTS_CONFIDENCE`), unless the scan is replayed or
+/// offline.
+async fn probe(
+    probe_client: &reqwest::Client,
+    config: &FinderConfig,
+    url: &str,
+    confidence: f32,
+) -> Result<Option<FinderMatch>, Box<dyn std::error::Error>> {
+    if let Some(fixtures) = config.fixtures.as_ref().filter(|f| f.is_replaying()) {
+        return fixtures.probe(url);
+    }
+    if config.offline {
+        return Ok(offline_match(config, url, confidence));
+    }
+    if let Some(found) = verify(probe_client, config, url).await? {
+        return Ok(Some(found));
+    }
+    if confidence < MIN_CASE_VARIANTS_CONFIDENCE {
+        return Ok(None);
+    }
+    for variant in link_utils::extension_case_variants(url) {
+        // Variants that are candidates as well are verified once.
+        if config.seen.mark(&variant) {
+            if let Some(found) = verify(probe_client, config, &variant).await? {
+                return Ok(Some(found));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Confirms a URL with the `Verifiers` of the `FinderConfig`, then as configured by the `FinderVerification` if
+/// they are undecided, and calls the `FinderHooks` with the verdict. Confirmed and rejected URLs are persisted as
+/// seen (cf. `SeenUrls::persist`), unlike URLs whose verification is inconclusive.
+async fn verify(
+    probe_client: &reqwest::Client,
+    config: &FinderConfig,
+    url: &str,
+) -> Result<Option<FinderMatch>, Box<dyn std::error::Error>> {
+    let verification: &dyn Verifier = match config.verification {
+        FinderVerification::Head => &HeadVerifier { get_fallback: true },
+        FinderVerification::HeadOnly => &HeadVerifier {
+            get_fallback: false,
+        },
+        FinderVerification::Sniff => &SniffVerifier,
+    };
+    let context = VerifyContext {
+        client: probe_client,
+        config,
+    };
+    let mut verdict = Verdict::Undecided;
+    for verifier in config.verifiers.iter().chain(std::iter::once(verification)) {
+        verdict = verifier.verify(url, &context).await?;
+        if verdict != Verdict::Undecided {
+            break;
+        }
+    }
+    config.hooks.probed(url, &verdict);
+    match verdict {
+        Verdict::Confirmed(found) => {
+            config.seen.persist(url)?;
+            Ok(Some(found))
+        }
+        Verdict::Rejected => {
+            config.seen.persist(url)?;
+            config.hooks.rejected(url);
+            Ok(None)
+        }
+        Verdict::Inconclusive | Verdict::Undecided => Ok(None),
+    }
+}
+
+/// Trusts a candidate URL offline if the cached REST API responses list it as is (cf. `MIN_OFFLINE_CONFIDENCE`),
+/// unless the `ProbeCache` knows it as missing.
+fn offline_match(config: &FinderConfig, url: &str, confidence: f32) -> Option<FinderMatch> {
+    if confidence < MIN_OFFLINE_CONFIDENCE
+        || config
+            .probe_cache
+            .as_ref()
+            .is_some_and(|cache| cache.get(url).is_some())
+    {
+        return None;
+    }
+    Some(FinderMatch {
+        url: url.to_string(),
+        mime_type: config.mime_types.get_by_url(url)?.to_string(),
+        probe: FinderProbe::Offline,
+        final_url: url.to_string(),
+        redirects: vec![],
+    })
+}
+
+/// Discovers the post types a WordPress website exposes in its REST API.
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// A `Result` containing the REST bases of the public post types (e.g. `posts`, `pages`, `media`), each of
+/// which can be scanned with `find` using the matching `FinderTarget`.
+pub async fn post_types(config: &FinderConfig) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    api::get_post_types(&reqwest::Client::new(), config).await
+}
+
+/// A configured scan of a WordPress website, built with `Finder::builder`.
+///
+/// Unlike `find_matches`, a `Finder` can use given HTTP clients, probe several candidate URLs at once and scan
+/// several sources in a row.
+///
+/// # Examples
+///
+/// ```rust
+/// use futures_util::pin_mut;
+/// use futures_util::stream::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let finder = pf_lib::Finder::builder()
+///         .url("http://example.com")
+///         .concurrency(8)
+///         .source(pf_lib::FinderSource::Api)
+///         .source(pf_lib::FinderSource::Feed)
+///         .build()
+///         .unwrap();
+///
+///     let stream = finder.find();
+///
+///     pin_mut!(stream); // needed for iteration
+///
+///     while let Some(res) = stream.next().await {
+///         match res {
+///             Ok(found) => println!("{}", found.url),
+///             Err(e) => eprintln!("{}", e),
+///         }
+///     }
+/// }
+/// ```
+pub struct Finder {
+    config: FinderConfig,
+    sources: Vec<FinderSource>,
+    client: reqwest::Client,
+    probe_client: reqwest::Client,
+    concurrency: usize,
+}
+
+impl Finder {
+    /// Creates a `FinderBuilder`.
+    pub fn builder() -> FinderBuilder {
+        FinderBuilder::default()
+    }
+
+    /// Returns the `FinderConfig`.
+    pub fn config(&self) -> &FinderConfig {
+        &self.config
+    }
+
+    /// Returns the `FinderConfig` to change it between scans (e.g. its `target`).
+    pub fn config_mut(&mut self) -> &mut FinderConfig {
+        &mut self.config
+    }
+
+    /// Creates an asynchronous stream that fetches the URLs of each source in turn, like `find_matches`.
+    ///
+    /// An error ending the stream of a source is yielded before moving on to the next source.
+    ///
+    /// # Returns
+    ///
+    /// A `futures_core::Stream` over the `FinderMatch` of existing URLs.
+    pub fn find(&self) -> impl Stream<Item = Result<FinderMatch, Box<dyn std::error::Error>>> + '_ {
+        stream! {
+            for source in &self.sources {
+                let stream = matches(
+                    &self.client,
+                    &self.probe_client,
+                    &self.config,
+                    *source,
+                    self.concurrency,
+                );
+                pin_mut!(stream);
+                while let Some(found) = stream.next().await {
+                    yield found;
+                }
+            }
+        }
+    }
+
+    /// Creates an asynchronous stream that lists the candidate URLs of each source in turn, like
+    /// `find_candidates`.
+    ///
+    /// An error ending the stream of a source is yielded before moving on to the next source.
+    ///
+    /// # Returns
+    ///
+    /// A `futures_core::Stream` over the `FinderCandidate`s.
+    pub fn candidates(
+        &self,
+    ) -> impl Stream<Item = Result<FinderCandidate, Box<dyn std::error::Error>>> + '_ {
+        stream! {
+            for source in &self.sources {
+                let stream = candidates(&self.client, &self.config, *source);
+                pin_mut!(stream);
+                while let Some(candidate) = stream.next().await {
+                    yield candidate;
                 }
             }
         }
     }
+
+    /// Discovers the post types the website exposes in its REST API, like `post_types`.
+    pub async fn post_types(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        api::get_post_types(&self.client, &self.config).await
+    }
+}
+
+/// Builder of a `Finder`.
+#[derive(Default)]
+pub struct FinderBuilder {
+    config: FinderConfig,
+    sources: Vec<FinderSource>,
+    client: Option<reqwest::Client>,
+    probe_client: Option<reqwest::Client>,
+    concurrency: Option<usize>,
+}
+
+impl FinderBuilder {
+    /// Sets the `FinderConfig`, replacing the settings made so far with `url`, `retry`, the extractors, the
+    /// verifiers and the hooks.
+    pub fn config(mut self, config: FinderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the WordPress base URL.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.config.url = url.into();
+        self
+    }
+
+    /// Sets the HTTP client of the sources, which defaults to `reqwest::Client::new()`.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the HTTP client of the probe requests, which should not follow redirects since the `Finder` follows
+    /// them by its `FinderRedirects` policy.
+    pub fn probe_client(mut self, client: reqwest::Client) -> Self {
+        self.probe_client = Some(client);
+        self
+    }
+
+    /// Sets how many candidate URLs are probed at once, which defaults to 1.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Sets how the requests of the sources and the probe requests are retried.
+    pub fn retry(mut self, retry: FinderRetry) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    /// Adds a source, scanned after the previous ones. Without any, the `source` of the `FinderConfig` is scanned.
+    pub fn source(mut self, source: FinderSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Sets the extractors of candidate URLs, which default to the built-in heuristics.
+    pub fn extractors(mut self, extractors: Extractors) -> Self {
+        self.config.extractors = extractors;
+        self
+    }
+
+    /// Adds an extractor of candidate URLs after the current ones.
+    pub fn extractor(mut self, extractor: impl Extractor + 'static) -> Self {
+        self.config.extractors.push(Arc::new(extractor));
+        self
+    }
+
+    /// Adds a verifier of candidate URLs after the current ones, before the `FinderVerification` (cf.
+    /// `FinderConfig::verifiers`).
+    pub fn verifier(mut self, verifier: impl Verifier + 'static) -> Self {
+        self.config.verifiers.push(Arc::new(verifier));
+        self
+    }
+
+    /// Sets the callbacks of the scan, replacing the hooks added so far.
+    pub fn hooks(mut self, hooks: FinderHooks) -> Self {
+        self.config.hooks = hooks;
+        self
+    }
+
+    /// Adds a hook called with each match before it is yielded (cf. `FinderHooks::on_match`).
+    pub fn on_match(mut self, hook: impl Fn(&FinderMatch) + Send + Sync + 'static) -> Self {
+        self.config.hooks = std::mem::take(&mut self.config.hooks).on_match(hook);
+        self
+    }
+
+    /// Adds a hook called with each rejected candidate URL (cf. `FinderHooks::on_reject`).
+    pub fn on_reject(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.config.hooks = std::mem::take(&mut self.config.hooks).on_reject(hook);
+        self
+    }
+
+    /// Adds a hook called with each verified candidate URL and its `Verdict` (cf. `FinderHooks::on_probe`).
+    pub fn on_probe(mut self, hook: impl Fn(&str, &Verdict) + Send + Sync + 'static) -> Self {
+        self.config.hooks = std::mem::take(&mut self.config.hooks).on_probe(hook);
+        self
+    }
+
+    /// Builds the `Finder`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Finder`, or an error if the default probe client cannot be built.
+    pub fn build(self) -> Result<Finder, Box<dyn std::error::Error>> {
+        let probe_client = match self.probe_client {
+            Some(probe_client) => probe_client,
+            None => link_utils::probe_client()?,
+        };
+        let sources = if self.sources.is_empty() {
+            vec![self.config.source]
+        } else {
+            self.sources
+        };
+        Ok(Finder {
+            config: self.config,
+            sources,
+            client: self.client.unwrap_or_default(),
+            probe_client,
+            concurrency: self.concurrency.unwrap_or(1),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "mp4")]
+    use crate::test_utils::AcfExtractor;
+    use crate::test_utils::TempDir;
 
     use futures_util::pin_mut;
     use futures_util::StreamExt;
     use serde_json::json;
 
+    #[cfg(feature = "mp4")]
     #[tokio::test]
     async fn test_find() {
         let mut server = mockito::Server::new_async().await;
@@ -150,71 +733,966 @@ mod tests {
         assert!(urls.contains(&format!("{}/wp-content/uploads/2022/02/video.mp4", url)));
     }
 
+    #[cfg(feature = "mp4")]
     #[tokio::test]
-    async fn test_find_with_no_urls() {
+    async fn test_find_across_pages_and_targets() {
         let mut server = mockito::Server::new_async().await;
 
-        let api_mock = server
+        let url = server.url();
+
+        let video = json!({"source_url": format!("{}/wp-content/uploads/2021/01/video.mp4", url)});
+
+        let media_page_1_mock = server
             .mock("GET", "/wp-json/wp/v2/media?per_page=100")
-            .with_body("[]")
+            .with_header(
+                "link",
+                &format!(r#"<{url}/wp-json/wp/v2/media?per_page=100&page=2>; rel="next""#),
+            )
+            .with_body(json!([video]).to_string())
             .create_async()
             .await;
 
-        let config = FinderConfig {
-            url: server.url(),
+        let media_page_2_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100&page=2")
+            .with_body(json!([video]).to_string())
+            .create_async()
+            .await;
+
+        let posts_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/posts?per_page=100&_embed=wp:featuredmedia",
+            )
+            .with_body(json!([{"_embedded": {"wp:featuredmedia": [video]}}]).to_string())
+            .create_async()
+            .await;
+
+        let video_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut config = FinderConfig {
+            url: url.clone(),
             ..Default::default()
         };
 
-        let stream = find(&config);
-        pin_mut!(stream);
-        let next = stream.next().await;
+        let media_urls = find(&config)
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
 
-        api_mock.assert_async().await;
+        config.target = crate::FinderTarget::Posts {
+            categories_exclude: vec![],
+            tags_exclude: vec![],
+        };
+        let posts_urls = find(&config)
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
 
-        assert!(next.is_none());
+        media_page_1_mock.assert_async().await;
+        media_page_2_mock.assert_async().await;
+        posts_mock.assert_async().await;
+        video_mock.assert_async().await;
+
+        assert_eq!(
+            media_urls,
+            vec![format!("{}/wp-content/uploads/2021/01/video.mp4", url)]
+        );
+        assert!(posts_urls.is_empty());
     }
 
+    #[cfg(all(feature = "mp4", feature = "mov"))]
     #[tokio::test]
-    async fn test_find_with_invalid_urls() {
+    async fn test_find_with_recorded_fixtures() {
+        let dir = TempDir::new("replay");
         let mut server = mockito::Server::new_async().await;
-
         let url = server.url();
 
-        let body = json!([{"source_url": format!("{}/wp-content/uploads/2021/01/image.jpg", url)}, {"_embedded": {"wp:featuredmedia": [{"source_url": format!("{}/wp-content/uploads/2021/01/video.mp4", url)}]}}]);
-
+        let body = json!([
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/video.mp4")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/missing.mov")}
+        ]);
         let api_mock = server
             .mock("GET", "/wp-json/wp/v2/media?per_page=100")
             .with_body(body.to_string())
             .create_async()
             .await;
-
-        let not_found_mock = server
-            .mock("HEAD", "/wp-content/uploads/2021/01/image.mp4")
+        let video_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
             .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        let missing_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/missing.mov")
             .with_status(404)
             .create_async()
             .await;
 
-        let invalid_header_mock = server
-            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
-            .with_header("Content-Type", "application/json")
-            .create_async()
+        let recording = FinderConfig {
+            url: url.clone(),
+            fixtures: Some(Fixtures::record(&dir).unwrap()),
+            ..Default::default()
+        };
+        let recorded = find_matches(&recording)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
             .await;
+        api_mock.assert_async().await;
+        video_mock.assert_async().await;
+        missing_mock.assert_async().await;
+        drop(server);
 
-        let config = FinderConfig {
-            url,
+        let mut replaying = FinderConfig {
+            url: url.clone(),
+            fixtures: Some(Fixtures::replay(&dir).unwrap()),
             ..Default::default()
         };
+        let replayed = find_matches(&replaying)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
 
-        let stream = find(&config);
-        pin_mut!(stream);
-        let next = stream.next().await;
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed[0].probe, FinderProbe::Head);
+
+        replaying.source = FinderSource::Feed;
+        let results = find(&replaying).collect::<Vec<_>>().await;
+        assert_eq!(
+            results[0].as_ref().unwrap_err().to_string(),
+            "Replayed scans only support the REST API source"
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_finder_builder() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/video.mp4")},
+            {"acf": {"video_file": format!("{url}/videos/intro.mp4")}}
+        ]);
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let video_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        let acf_mock = server
+            .mock("HEAD", "/videos/intro.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+
+        let finder = Finder::builder()
+            .url(&url)
+            .client(reqwest::Client::new())
+            .concurrency(4)
+            .retry(FinderRetry {
+                max_retries: 1,
+                ..Default::default()
+            })
+            .source(FinderSource::Api)
+            .extractor(AcfExtractor)
+            .build()
+            .unwrap();
+        assert_eq!(
+            finder.config().extractors.names().collect::<Vec<_>>(),
+            ["p1", "p2", "p3", "acf"]
+        );
+
+        let mut urls = finder
+            .find()
+            .map(|res| res.unwrap().url)
+            .collect::<Vec<_>>()
+            .await;
+        urls.sort();
 
+        assert_eq!(
+            urls,
+            [
+                format!("{url}/videos/intro.mp4"),
+                format!("{url}/wp-content/uploads/2021/01/video.mp4")
+            ]
+        );
         api_mock.assert_async().await;
-        not_found_mock.assert_async().await;
-        invalid_header_mock.assert_async().await;
+        video_mock.assert_async().await;
+        acf_mock.assert_async().await;
 
-        assert!(next.is_none());
+        let finder = Finder::builder()
+            .url(&url)
+            .extractors(Extractors::empty().with(AcfExtractor))
+            .build()
+            .unwrap();
+        let urls = finder
+            .find()
+            .map(|res| res.unwrap().url)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(urls, [format!("{url}/videos/intro.mp4")]);
+
+        let finder = Finder::builder()
+            .url(&url)
+            .extractors(Extractors::empty().with(AcfExtractor))
+            .verifier(crate::TrustVerifier)
+            .build()
+            .unwrap();
+        let matches = finder.find().map(Result::unwrap).collect::<Vec<_>>().await;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].probe, FinderProbe::Trusted);
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_finder_builder_with_hooks() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"acf": {"video_file": format!("{url}/video.mp4")}},
+            {"acf": {"video_file": format!("{url}/missing.mp4")}},
+            {"acf": {"video_file": format!("{url}/unavailable.mp4")}}
+        ]);
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let video_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        // Rejected URLs are probed again with the case variants of their extension.
+        let missing_mock = server
+            .mock(
+                "HEAD",
+                mockito::Matcher::Regex(r"^/missing\.(mp4|MP4)$".to_string()),
+            )
+            .with_status(404)
+            .expect(2)
+            .create_async()
+            .await;
+        // Unavailable URLs are retried once.
+        let unavailable_mock = server
+            .mock(
+                "HEAD",
+                mockito::Matcher::Regex(r"^/unavailable\.(mp4|MP4)$".to_string()),
+            )
+            .with_status(503)
+            .expect(4)
+            .create_async()
+            .await;
+
+        let matched = Arc::new(std::sync::Mutex::new(vec![]));
+        let rejected = Arc::new(std::sync::Mutex::new(vec![]));
+        let probed = Arc::new(std::sync::Mutex::new(vec![]));
+        let finder = Finder::builder()
+            .url(&url)
+            .retry(FinderRetry {
+                max_retries: 1,
+                backoff: std::time::Duration::from_millis(1),
+            })
+            .extractors(Extractors::empty().with(AcfExtractor))
+            .on_match({
+                let matched = matched.clone();
+                move |found| matched.lock().unwrap().push(found.url.clone())
+            })
+            .on_reject({
+                let rejected = rejected.clone();
+                move |url| rejected.lock().unwrap().push(url.to_string())
+            })
+            .on_probe({
+                let probed = probed.clone();
+                move |url, verdict| {
+                    probed
+                        .lock()
+                        .unwrap()
+                        .push((url.to_string(), verdict.clone()))
+                }
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", finder.config().hooks),
+            "FinderHooks { on_match: 1, on_reject: 1, on_probe: 1 }"
+        );
+
+        let matches = finder.find().map(Result::unwrap).collect::<Vec<_>>().await;
+
+        api_mock.assert_async().await;
+        video_mock.assert_async().await;
+        missing_mock.assert_async().await;
+        unavailable_mock.assert_async().await;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(*matched.lock().unwrap(), [format!("{url}/video.mp4")]);
+        let mut rejected = rejected.lock().unwrap().clone();
+        rejected.sort();
+        assert_eq!(
+            rejected,
+            [format!("{url}/missing.MP4"), format!("{url}/missing.mp4")]
+        );
+        let mut probed = probed.lock().unwrap().clone();
+        probed.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            probed,
+            [
+                (format!("{url}/missing.MP4"), Verdict::Rejected),
+                (format!("{url}/missing.mp4"), Verdict::Rejected),
+                (format!("{url}/unavailable.MP4"), Verdict::Inconclusive),
+                (format!("{url}/unavailable.mp4"), Verdict::Inconclusive),
+                (
+                    format!("{url}/video.mp4"),
+                    Verdict::Confirmed(matches[0].clone())
+                ),
+            ]
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_find_with_case_sensitive_server() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/Video-Final.MP4")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/Clip.mp4")},
+            {"link": format!("{url}/post-slug"), "date": "2021-01-01T00:00:00"}
+        ]);
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let video_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/Video-Final.MP4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        let clip_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/Clip.MP4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        // Guesses are not worth their case variants.
+        let guess_variants_mock = server
+            .mock(
+                "HEAD",
+                mockito::Matcher::Regex(r"post-slug\.[A-Z]".to_string()),
+            )
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: url.clone(),
+            ..Default::default()
+        };
+        let mut urls = find(&config).map(Result::unwrap).collect::<Vec<_>>().await;
+        urls.sort();
+
+        assert_eq!(
+            urls,
+            [
+                format!("{url}/wp-content/uploads/2021/01/Clip.MP4"),
+                format!("{url}/wp-content/uploads/2021/01/Video-Final.MP4")
+            ]
+        );
+        api_mock.assert_async().await;
+        video_mock.assert_async().await;
+        clip_mock.assert_async().await;
+        guess_variants_mock.assert_async().await;
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_find_with_seen_file() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/missing.mp4")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/unavailable.mp4")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/soft-404.mp4")}
+        ]);
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let missing_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/missing.mp4")
+            .with_status(404)
+            .create_async()
+            .await;
+        let unavailable_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/unavailable.mp4")
+            .with_status(503)
+            .create_async()
+            .await;
+        let soft_404_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/soft-404.mp4")
+            .with_header("Content-Type", "text/html; charset=UTF-8")
+            .with_body("<!DOCTYPE html><html><body>Not found</body></html>")
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("seen_find");
+        let path = dir.join("seen.txt");
+        let config = FinderConfig {
+            url: url.clone(),
+            verification: FinderVerification::Sniff,
+            seen: crate::SeenUrls::with_file(&path).unwrap(),
+            ..Default::default()
+        };
+        let results = find_matches(&config).collect::<Vec<_>>().await;
+
+        // The missing URL is rejected, neither the unavailable server nor the content mismatch of the soft 404 is
+        // a verdict.
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        let persisted = std::fs::read_to_string(&path).unwrap();
+        assert!(persisted.contains(&format!("{url}/wp-content/uploads/2021/01/missing.mp4\n")));
+        assert!(!persisted.contains("unavailable"));
+        assert!(!persisted.contains("soft-404"));
+        api_mock.assert_async().await;
+        missing_mock.assert_async().await;
+        unavailable_mock.assert_async().await;
+        soft_404_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_candidates() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"id": 1, "content": {"rendered": format!("{url}/wp-content/uploads/2021/01/a.mp4 {url}/wp-content/uploads/2021/01/b.mp4")}},
+            {"id": 2, "content": {"rendered": format!("{url}/wp-content/uploads/2021/01/a.mp4")}, "link": format!("{url}/post-slug"), "date": "2021-01-01T00:00:00"}
+        ]);
+        let api_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/posts?per_page=100&_embed=wp:featuredmedia",
+            )
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let head_mock = server
+            .mock("HEAD", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: url.clone(),
+            target: crate::FinderTarget::Posts {
+                categories_exclude: vec![],
+                tags_exclude: vec![],
+            },
+            mime_types: crate::MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            budget: crate::FinderBudget {
+                min_confidence: 0.5,
+                max_guesses_per_item: None,
+            },
+            ..Default::default()
+        };
+        config
+            .seen
+            .insert(&format!("{url}/wp-content/uploads/2021/01/b.mp4"))
+            .unwrap();
+
+        let candidates = find_candidates(&config)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        api_mock.assert_async().await;
+        head_mock.assert_async().await;
+        // Two content links in the first item, then one content link and two slug guesses in the second item.
+        assert_eq!(candidates.len(), 5);
+        assert_eq!(
+            candidates[0],
+            FinderCandidate {
+                url: format!("{url}/wp-content/uploads/2021/01/a.mp4"),
+                confidence: 0.9,
+                reason: "content.rendered".to_string(),
+                heuristic: "p2".to_string(),
+                item_id: Some(1),
+                probed: true,
+            }
+        );
+        // Seen, listed before and below the minimum confidence.
+        assert!(candidates[1..].iter().all(|candidate| !candidate.probed));
+        assert_eq!(candidates[2].item_id, Some(2));
+        assert_eq!(candidates[4].heuristic, "p3");
+        assert_eq!(candidates[0].to_json()["probed"], true);
+        assert!(config
+            .seen
+            .contains(&format!("{url}/wp-content/uploads/2021/01/b.mp4")));
+        assert_eq!(config.seen.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_candidates_across_calls() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let body = json!([
+            {"id": 1, "content": {"rendered": format!("{url}/wp-content/uploads/2021/01/a.mp4 {url}/wp-content/uploads/2021/01/missing.mp4")}}
+        ]);
+        let api_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/posts?per_page=100&_embed=wp:featuredmedia",
+            )
+            .with_body(body.to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("candidates_cache");
+        let probe_cache =
+            crate::ProbeCache::open(&dir, std::time::Duration::from_secs(3600)).unwrap();
+        probe_cache
+            .insert(
+                &format!("{url}/wp-content/uploads/2021/01/missing.mp4"),
+                crate::ProbeEntry {
+                    status: 404,
+                    content_type: None,
+                    length: None,
+                    etag: None,
+                    timestamp: u64::MAX,
+                },
+            )
+            .unwrap();
+        let config = FinderConfig {
+            url: url.clone(),
+            target: crate::FinderTarget::Posts {
+                categories_exclude: vec![],
+                tags_exclude: vec![],
+            },
+            mime_types: crate::MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            probe_cache: Some(probe_cache),
+            ..Default::default()
+        };
+        let probed = || async {
+            find_candidates(&config)
+                .map(|candidate| {
+                    let candidate = candidate.unwrap();
+                    (candidate.url, candidate.probed)
+                })
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        // The missing file is known by the cache, then the other one is listed by the first call.
+        assert_eq!(
+            probed().await,
+            [
+                (format!("{url}/wp-content/uploads/2021/01/a.mp4"), true),
+                (
+                    format!("{url}/wp-content/uploads/2021/01/missing.mp4"),
+                    false
+                )
+            ]
+        );
+        assert!(probed().await.iter().all(|(_, probed)| !probed));
+        api_mock.assert_async().await;
+        assert!(config.seen.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_candidates_with_upload_bases_of_media() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let media = json!([{
+            "source_url": "https://cdn.example.com/media/2021/01/image.jpg",
+            "media_details": {"file": "2021/01/image.jpg"}
+        }]);
+        let posts = json!([{"link": format!("{url}/post-slug"), "date": "2021-01-01T00:00:00"}]);
+        let media_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(media.to_string())
+            .create_async()
+            .await;
+        let posts_mock = server
+            .mock(
+                "GET",
+                "/wp-json/wp/v2/posts?per_page=100&_embed=wp:featuredmedia",
+            )
+            .with_body(posts.to_string())
+            .create_async()
+            .await;
+
+        let mut config = FinderConfig {
+            url: url.clone(),
+            mime_types: crate::MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            ..Default::default()
+        };
+        find_candidates(&config).collect::<Vec<_>>().await;
+        assert_eq!(config.upload_bases.len(), 1);
+
+        config.target = crate::FinderTarget::Posts {
+            categories_exclude: vec![],
+            tags_exclude: vec![],
+        };
+        let candidates = find_candidates(&config)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        media_mock.assert_async().await;
+        posts_mock.assert_async().await;
+        assert!(candidates.iter().any(
+            |candidate| candidate.url == "https://cdn.example.com/media/2021/01/post-slug.mp4"
+        ));
+    }
+
+    #[cfg(all(feature = "mp4", feature = "mov"))]
+    #[tokio::test]
+    async fn test_find_offline() {
+        let dir = TempDir::new("offline");
+        let response_cache =
+            crate::ResponseCache::open(&dir, std::time::Duration::from_secs(3600)).unwrap();
+        let probe_cache =
+            crate::ProbeCache::open(&dir, std::time::Duration::from_secs(3600)).unwrap();
+
+        let url = "http://example.com";
+        let body = json!([
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/video.mp4")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/missing.mov")},
+            {"source_url": format!("{url}/wp-content/uploads/2021/01/poster.jpg")}
+        ]);
+        response_cache
+            .insert(
+                &format!("{url}/wp-json/wp/v2/media?per_page=100"),
+                &crate::CachedResponse {
+                    etag: None,
+                    last_modified: None,
+                    next_link: None,
+                    body: body.to_string(),
+                },
+            )
+            .unwrap();
+        probe_cache
+            .insert(
+                &format!("{url}/wp-content/uploads/2021/01/missing.mov"),
+                crate::ProbeEntry {
+                    status: 404,
+                    content_type: None,
+                    length: None,
+                    etag: None,
+                    timestamp: u64::MAX,
+                },
+            )
+            .unwrap();
+
+        let mut config = FinderConfig {
+            url: url.to_string(),
+            response_cache: Some(response_cache),
+            probe_cache: Some(probe_cache),
+            offline: true,
+            ..Default::default()
+        };
+
+        // The URLs guessed from the poster are only listed, as candidates that would not be trusted.
+        let candidates = find_candidates(&config)
+            .map(|candidate| candidate.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        let trusted = candidates
+            .iter()
+            .filter(|candidate| candidate.probed)
+            .map(|candidate| candidate.url.as_str())
+            .collect::<Vec<_>>();
+        assert!(candidates.len() > 2);
+        assert_eq!(
+            trusted,
+            [format!("{url}/wp-content/uploads/2021/01/video.mp4")]
+        );
+
+        let matches = find_matches(&config).collect::<Vec<_>>().await;
+
+        assert_eq!(matches.len(), 1);
+        let found = matches[0].as_ref().unwrap();
+        assert_eq!(
+            found.url,
+            format!("{url}/wp-content/uploads/2021/01/video.mp4")
+        );
+        assert_eq!(found.mime_type, "video/mp4");
+        assert_eq!(found.probe, FinderProbe::Offline);
+
+        config.source = FinderSource::Sitemap;
+        let results = find(&config).collect::<Vec<_>>().await;
+        assert_eq!(
+            results[0].as_ref().unwrap_err().to_string(),
+            "Offline scans only support the REST API source"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_with_no_urls() {
+        let mut server = mockito::Server::new_async().await;
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let stream = find(&config);
+        pin_mut!(stream);
+        let next = stream.next().await;
+
+        api_mock.assert_async().await;
+
+        assert!(next.is_none());
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_find_with_invalid_urls() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let body = json!([{"source_url": format!("{}/wp-content/uploads/2021/01/image.jpg", url)}, {"_embedded": {"wp:featuredmedia": [{"source_url": format!("{}/wp-content/uploads/2021/01/video.mp4", url)}]}}]);
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let not_found_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/image.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let invalid_header_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
+            .with_header("Content-Type", "application/json")
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url,
+            ..Default::default()
+        };
+
+        let stream = find(&config);
+        pin_mut!(stream);
+        let next = stream.next().await;
+
+        api_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+        invalid_header_mock.assert_async().await;
+
+        assert!(next.is_none());
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_find_matches_with_get_fallback() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let body = json!([{"source_url": format!("{}/wp-content/uploads/2021/01/video.mp4", url)}]);
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let head_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mp4")
+            .with_status(403)
+            .create_async()
+            .await;
+
+        let get_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/video.mp4")
+            .match_header("range", "bytes=0-0")
+            .with_status(206)
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: url.clone(),
+            ..Default::default()
+        };
+
+        let stream = find_matches(&config);
+        pin_mut!(stream);
+        let matches = stream
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
+
+        api_mock.assert_async().await;
+        head_mock.assert_async().await;
+        get_mock.assert_async().await;
+
+        assert_eq!(
+            matches,
+            vec![FinderMatch {
+                url: format!("{}/wp-content/uploads/2021/01/video.mp4", url),
+                mime_type: "video/mp4".to_string(),
+                probe: FinderProbe::RangeGet,
+                final_url: format!("{}/wp-content/uploads/2021/01/video.mp4", url),
+                redirects: vec![],
+            }]
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_find_with_sniff_verification() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let body = json!([{"source_url": format!("{}/wp-content/uploads/2021/01/image.jpg", url)}, {"_embedded": {"wp:featuredmedia": [{"source_url": format!("{}/wp-content/uploads/2021/01/video.mp4", url)}]}}]);
+
+        let api_mock = server
+            .mock("GET", "/wp-json/wp/v2/media?per_page=100")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let soft_404_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/image.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .with_body("<html>Not found</html>")
+            .create_async()
+            .await;
+
+        let video_mock = server
+            .mock("GET", "/wp-content/uploads/2021/01/video.mp4")
+            .match_header("range", "bytes=0-4095")
+            .with_status(206)
+            .with_header("Content-Type", "video/mp4")
+            .with_body(b"\x00\x00\x00\x20ftypisom\x00\x00\x02\x00")
+            .create_async()
+            .await;
+
+        let head_mock = server
+            .mock("HEAD", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: url.clone(),
+            verification: FinderVerification::Sniff,
+            ..Default::default()
+        };
+
+        let stream = find(&config);
+        pin_mut!(stream);
+        let results = stream.collect::<Vec<_>>().await;
+
+        api_mock.assert_async().await;
+        soft_404_mock.assert_async().await;
+        video_mock.assert_async().await;
+        head_mock.assert_async().await;
+
+        let urls = results
+            .iter()
+            .filter_map(|res| res.as_ref().ok())
+            .collect::<Vec<_>>();
+        let errors = results
+            .iter()
+            .filter_map(|res| res.as_ref().err())
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            urls,
+            vec![&format!("{}/wp-content/uploads/2021/01/video.mp4", url)]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(&format!(
+            "Content mismatch for {}/wp-content/uploads/2021/01/image.mp4",
+            url
+        )));
+    }
+
+    #[cfg(feature = "mov")]
+    #[tokio::test]
+    async fn test_find_with_sitemap_source() {
+        let mut server = mockito::Server::new_async().await;
+
+        let url = server.url();
+
+        let sitemap_mock = server
+            .mock("GET", "/wp-sitemap.xml")
+            .with_body(format!("<urlset><url><loc>{url}/hello-world/</loc><video:video><video:content_loc>{url}/wp-content/uploads/2021/01/video.mov</video:content_loc></video:video></url></urlset>"))
+            .create_async()
+            .await;
+
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(404)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let video_mock = server
+            .mock("HEAD", "/wp-content/uploads/2021/01/video.mov")
+            .with_header("Content-Type", "video/quicktime")
+            .create_async()
+            .await;
+
+        let config = FinderConfig {
+            url: url.clone(),
+            source: FinderSource::Sitemap,
+            ..Default::default()
+        };
+
+        let stream = find(&config);
+        pin_mut!(stream);
+
+        let urls = stream
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
+
+        sitemap_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+        video_mock.assert_async().await;
+
+        assert_eq!(
+            urls,
+            vec![format!("{}/wp-content/uploads/2021/01/video.mov", url)]
+        );
     }
 
     #[tokio::test]
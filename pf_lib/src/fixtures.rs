@@ -0,0 +1,214 @@
+use crate::response_cache::{CachedResponse, ResponseCache};
+use crate::FinderMatch;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Name of the probe results file in the fixtures directory.
+const PROBES_FILE: &str = "probes.jsonl";
+
+/// Name of the REST API responses directory in the fixtures directory.
+const RESPONSES_DIR: &str = "responses";
+
+/// Result of probing a candidate URL, as recorded: the match, nothing if the URL was not confirmed, or the error
+/// message of a mismatch.
+type ProbeResult = Result<Option<FinderMatch>, String>;
+
+/// Recorded HTTP traffic of a scan, used as deterministic fixtures.
+///
+/// When recording, every REST API response and probe result is written to a directory. When replaying, they are
+/// served from that directory without any request, candidate URLs that were not recorded being treated as
+/// missing. Clones share the same fixtures.
+#[derive(Clone, Debug)]
+pub struct Fixtures {
+    responses: ResponseCache,
+    probes: Arc<Mutex<HashMap<String, ProbeResult>>>,
+    file: Option<Arc<Mutex<LineWriter<File>>>>,
+}
+
+impl Fixtures {
+    /// Starts recording to a directory, replacing the probe results it holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The fixtures directory, which is created if it does not exist.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Fixtures`, or an error if the directory cannot be written.
+    pub fn record(dir: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let responses = ResponseCache::open(dir.as_ref().join(RESPONSES_DIR), Duration::MAX)?;
+        let file = File::create(dir.as_ref().join(PROBES_FILE))
+            .map_err(|e| format!("Failed to create fixtures: {e}"))?;
+        Ok(Self {
+            responses,
+            probes: Arc::default(),
+            file: Some(Arc::new(Mutex::new(LineWriter::new(file)))),
+        })
+    }
+
+    /// Loads the fixtures of a directory to replay them.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The fixtures directory, written by `record`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Fixtures`, or an error if the directory cannot be read.
+    pub fn replay(dir: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = File::open(dir.as_ref().join(PROBES_FILE))
+            .map_err(|e| format!("Failed to open fixtures: {e}"))?;
+        let mut probes = HashMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("Failed to read fixtures: {e}"))?;
+            // Lines truncated by an interrupted recording are ignored.
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let Some(url) = json["url"].as_str() else {
+                continue;
+            };
+            let probed = match json["error"].as_str() {
+                Some(error) => Err(error.to_string()),
+                None => Ok(FinderMatch::from_json(&json["match"])),
+            };
+            probes.insert(url.to_string(), probed);
+        }
+        Ok(Self {
+            responses: ResponseCache::open(dir.as_ref().join(RESPONSES_DIR), Duration::MAX)?,
+            probes: Arc::new(Mutex::new(probes)),
+            file: None,
+        })
+    }
+
+    /// Checks whether the fixtures are replayed rather than recorded.
+    pub fn is_replaying(&self) -> bool {
+        self.file.is_none()
+    }
+
+    /// Returns the recorded REST API response of a URL.
+    pub fn response(&self, url: &str) -> Option<CachedResponse> {
+        self.responses.get(url)
+    }
+
+    /// Records the REST API response of a URL.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing, or an error if the response cannot be written.
+    pub fn record_response(
+        &self,
+        url: &str,
+        response: &CachedResponse,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.responses.insert(url, response)
+    }
+
+    /// Returns the recorded probe result of a candidate URL, which is nothing if the URL was not recorded.
+    pub fn probe(&self, url: &str) -> Result<Option<FinderMatch>, Box<dyn std::error::Error>> {
+        match self.probes.lock().unwrap().get(url) {
+            Some(Ok(found)) => Ok(found.clone()),
+            Some(Err(e)) => Err(e.clone().into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Records the probe result of a candidate URL.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing, or an error if the result cannot be written.
+    pub fn record_probe(
+        &self,
+        url: &str,
+        probed: &Result<Option<FinderMatch>, Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let probed = match probed {
+            Ok(found) => Ok(found.clone()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Some(file) = &self.file {
+            let json = serde_json::json!({
+                "url": url,
+                "match": probed.as_ref().ok().and_then(Option::as_ref).map(FinderMatch::to_json),
+                "error": probed.as_ref().err(),
+            });
+            writeln!(file.lock().unwrap(), "{json}")
+                .map_err(|e| format!("Failed to write fixtures: {e}"))?;
+        }
+        self.probes.lock().unwrap().insert(url.to_string(), probed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+    use crate::FinderProbe;
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = TempDir::new("fixtures");
+        let found = FinderMatch {
+            url: "http://example.com/a.mp4".to_string(),
+            mime_type: "video/mp4".to_string(),
+            probe: FinderProbe::RangeGet,
+            final_url: "http://cdn.example.com/a.mp4".to_string(),
+            redirects: vec!["http://cdn.example.com/a.mp4".to_string()],
+        };
+        let response = CachedResponse {
+            etag: None,
+            last_modified: None,
+            next_link: None,
+            body: "[]".to_string(),
+        };
+
+        let recording = Fixtures::record(&dir).unwrap();
+        assert!(!recording.is_replaying());
+        recording
+            .record_response("http://example.com/wp-json/wp/v2/media", &response)
+            .unwrap();
+        recording
+            .record_probe("http://example.com/a.mp4", &Ok(Some(found.clone())))
+            .unwrap();
+        recording
+            .record_probe("http://example.com/b.mp4", &Ok(None))
+            .unwrap();
+        recording
+            .record_probe("http://example.com/c.mp4", &Err("Content mismatch".into()))
+            .unwrap();
+        drop(recording);
+
+        let replaying = Fixtures::replay(&dir).unwrap();
+        assert!(replaying.is_replaying());
+        assert_eq!(
+            replaying.response("http://example.com/wp-json/wp/v2/media"),
+            Some(response)
+        );
+        assert_eq!(
+            replaying.probe("http://example.com/a.mp4").unwrap(),
+            Some(found)
+        );
+        assert_eq!(replaying.probe("http://example.com/b.mp4").unwrap(), None);
+        assert_eq!(
+            replaying
+                .probe("http://example.com/c.mp4")
+                .unwrap_err()
+                .to_string(),
+            "Content mismatch"
+        );
+        assert_eq!(replaying.probe("http://example.com/d.mp4").unwrap(), None);
+    }
+
+    #[test]
+    fn test_replay_without_recording() {
+        let dir = TempDir::new("no_fixtures");
+        assert!(Fixtures::replay(dir.join("missing")).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::{FinderMatch, Verdict};
+
+type MatchHook = Arc<dyn Fn(&FinderMatch) + Send + Sync>;
+type RejectHook = Arc<dyn Fn(&str) + Send + Sync>;
+type ProbeHook = Arc<dyn Fn(&str, &Verdict) + Send + Sync>;
+
+/// Callbacks of a scan, e.g. to report its progress or count its probes without consuming its stream.
+///
+/// Hooks are called in the order they were added. Clones share the same hooks.
+#[derive(Clone, Default)]
+pub struct FinderHooks {
+    on_match: Vec<MatchHook>,
+    on_reject: Vec<RejectHook>,
+    on_probe: Vec<ProbeHook>,
+}
+
+impl std::fmt::Debug for FinderHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FinderHooks")
+            .field("on_match", &self.on_match.len())
+            .field("on_reject", &self.on_reject.len())
+            .field("on_probe", &self.on_probe.len())
+            .finish()
+    }
+}
+
+impl FinderHooks {
+    /// Adds a hook called with each `FinderMatch` before the scan yields it, including the matches of offline and
+    /// replayed scans.
+    pub fn on_match(mut self, hook: impl Fn(&FinderMatch) + Send + Sync + 'static) -> Self {
+        self.on_match.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a hook called with each candidate URL that the verifiers reject, i.e. that is missing or of another
+    /// type.
+    pub fn on_reject(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.on_reject.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a hook called with each candidate URL once verified, along with the `Verdict` of the verifiers, which is
+    /// `Verdict::Undecided` if none of them decided.
+    pub fn on_probe(mut self, hook: impl Fn(&str, &Verdict) + Send + Sync + 'static) -> Self {
+        self.on_probe.push(Arc::new(hook));
+        self
+    }
+
+    /// Calls the `on_match` hooks.
+    pub(crate) fn matched(&self, found: &FinderMatch) {
+        self.on_match.iter().for_each(|hook| hook(found));
+    }
+
+    /// Calls the `on_reject` hooks.
+    pub(crate) fn rejected(&self, url: &str) {
+        self.on_reject.iter().for_each(|hook| hook(url));
+    }
+
+    /// Calls the `on_probe` hooks.
+    pub(crate) fn probed(&self, url: &str, verdict: &Verdict) {
+        self.on_probe.iter().for_each(|hook| hook(url, verdict));
+    }
+}
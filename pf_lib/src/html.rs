@@ -0,0 +1,566 @@
+use crate::{date_utils, feed, link_utils, sitemap, FinderConfig, FinderRetry};
+
+use async_stream::try_stream;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Meta tags (`property` or `name`) holding the URL of a video or audio file.
+const MEDIA_META_TAGS: &[&str] = &[
+    "og:video",
+    "og:video:url",
+    "og:video:secure_url",
+    "og:audio",
+    "og:audio:url",
+    "og:audio:secure_url",
+    "twitter:player:stream",
+];
+
+/// JSON-LD types whose `contentUrl` is the URL of a media file.
+const MEDIA_OBJECT_TYPES: &[&str] = &["VideoObject", "AudioObject"];
+
+lazy_static! {
+    /// Regular expression to match `<meta>` tags.
+    static ref META_RE: Regex = Regex::new(r"(?is)<meta\s[^>]*>").unwrap();
+
+    /// Regular expression to match and capture the name and value of an attribute of a tag.
+    static ref ATTRIBUTE_RE: Regex =
+        Regex::new(r#"(?is)([a-z:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    /// Regular expression to match and capture the ID of a post, page or attachment in the classes of the body.
+    static ref BODY_ID_RE: Regex = Regex::new(
+        r#"(?is)<body\s[^>]*class\s*=\s*["'][^"']*\b(?:postid|page-id|attachmentid)-(\d+)\b"#
+    )
+    .unwrap();
+
+    /// Regular expression to match and capture the content of JSON-LD scripts.
+    static ref JSON_LD_RE: Regex =
+        Regex::new(r#"(?is)<script[^>]+type\s*=\s*["']application/ld\+json["'][^>]*>(.*?)</script>"#).unwrap();
+}
+
+/// Returns the lowercase names and decoded values of the attributes of an HTML or XML tag.
+pub fn attributes(tag: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    ATTRIBUTE_RE.captures_iter(tag).filter_map(|captures| {
+        let value = captures.get(2).or(captures.get(3))?.as_str();
+        Some((captures[1].to_lowercase(), sitemap::decode(value)))
+    })
+}
+
+/// Returns the `(property or name, content)` pairs of the `<meta>` tags of an HTML page.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    META_RE
+        .find_iter(html)
+        .filter_map(|tag| {
+            let mut key = None;
+            let mut content = None;
+            for (name, value) in attributes(tag.as_str()) {
+                match name.as_str() {
+                    "property" | "name" => key = Some(value.to_lowercase()),
+                    "content" => content = Some(value),
+                    _ => {}
+                }
+            }
+            Some((key?, content?))
+        })
+        .collect()
+}
+
+/// Collects the `contentUrl` of every `VideoObject` and `AudioObject` of a JSON-LD document, including nested
+/// ones (e.g. in `@graph`).
+fn media_objects(value: &serde_json::Value, urls: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Array(values) => values.iter().for_each(|v| media_objects(v, urls)),
+        serde_json::Value::Object(object) => {
+            let is_media = match &object.get("@type") {
+                Some(serde_json::Value::String(t)) => MEDIA_OBJECT_TYPES.contains(&t.as_str()),
+                Some(serde_json::Value::Array(types)) => types
+                    .iter()
+                    .filter_map(|t| t.as_str())
+                    .any(|t| MEDIA_OBJECT_TYPES.contains(&t)),
+                _ => false,
+            };
+            if is_media {
+                if let Some(url) = object.get("contentUrl").and_then(|v| v.as_str()) {
+                    urls.push(url.to_string());
+                }
+            }
+            object.values().for_each(|v| media_objects(v, urls));
+        }
+        _ => {}
+    }
+}
+
+/// Converts an HTML page to items shaped like WordPress REST API items.
+///
+/// The page becomes a post item whose `id` comes from the classes of the body (e.g. `postid-12`), whose `content`
+/// is the raw HTML, whose `date` and `modified` come from the `article:published_time` and
+/// `article:modified_time` meta tags and whose featured media is the `og:image`.
+/// Videos and audio files announced by `og:video`, `og:audio`, `twitter:player:stream` and JSON-LD `VideoObject`
+/// and `AudioObject` metadata become media items.
+///
+/// # Arguments
+///
+/// * `link` - The URL of the page.
+/// * `html` - The HTML of the page.
+///
+/// # Returns
+///
+/// A `Vec` of JSON items.
+fn parse_page(link: &str, html: &str) -> Vec<serde_json::Value> {
+    let meta = meta_tags(html);
+    let find_meta = |key: &str| meta.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+
+    let mut media = meta
+        .iter()
+        .filter(|(key, _)| MEDIA_META_TAGS.contains(&key.as_str()))
+        .map(|(_, url)| url.clone())
+        .collect::<Vec<_>>();
+    for captures in JSON_LD_RE.captures_iter(html) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&captures[1]) {
+            media_objects(&json, &mut media);
+        }
+    }
+    media.dedup();
+
+    let id = BODY_ID_RE
+        .captures(html)
+        .and_then(|captures| captures[1].parse::<u64>().ok());
+    let post = serde_json::json!({
+        "id": id,
+        "link": link,
+        "date": find_meta("article:published_time").and_then(date_utils::normalize),
+        "modified": find_meta("article:modified_time").and_then(date_utils::normalize),
+        "content": {"rendered": html},
+        "_embedded": {"wp:featuredmedia": find_meta("og:image").map(|url| serde_json::json!({"source_url": url})).into_iter().collect::<Vec<_>>()},
+    });
+
+    std::iter::once(post)
+        .chain(
+            media
+                .into_iter()
+                .map(|url| serde_json::json!({ "source_url": url })),
+        )
+        .collect()
+}
+
+/// Fetches a page and converts it with `parse_page`.
+///
+/// # Returns
+///
+/// A `Result` containing `None` if the page does not exist, the items of the page otherwise, or an error.
+async fn get_page(
+    client: &reqwest::Client,
+    url: &str,
+    retry: &FinderRetry,
+) -> Result<Option<Vec<serde_json::Value>>, Box<dyn std::error::Error>> {
+    let response = link_utils::send_with_retry(client.get(url), retry)
+        .await
+        .map_err(|e| format!("Failed to send request: {e}"))?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    // `?p=ID` links redirect to the permalink.
+    let link = response.url().to_string();
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {e}"))?;
+
+    Ok(Some(parse_page(&link, &html)))
+}
+
+/// Fetches the page of a `?p=ID` link with `get_page`.
+///
+/// Soft 404s are missing pages as well: redirects to the home page, and pages that are not a post (i.e. with
+/// neither ID nor publication date).
+///
+/// # Returns
+///
+/// A `Result` containing `None` if the post does not exist, the items of its page otherwise, or an error.
+async fn get_post_page(
+    client: &reqwest::Client,
+    config: &FinderConfig,
+    id: u32,
+) -> Result<Option<Vec<serde_json::Value>>, Box<dyn std::error::Error>> {
+    let home = config.url.trim_end_matches('/');
+    let Some(items) = get_page(client, &format!("{home}/?p={id}"), &config.retry).await? else {
+        return Ok(None);
+    };
+    let is_home = items[0]["link"]
+        .as_str()
+        .is_some_and(|link| link.trim_end_matches('/') == home);
+    let is_post = !items[0]["id"].is_null() || !items[0]["date"].is_null();
+    Ok((!is_home && is_post).then_some(items))
+}
+
+/// Checks whether the page converted with `parse_page` was published within the `FinderConfig` date bounds.
+fn is_in_range(config: &FinderConfig, items: &[serde_json::Value]) -> bool {
+    date_utils::is_in_range(
+        config,
+        items[0]["date"].as_str(),
+        items[0]["modified"].as_str(),
+    )
+}
+
+/// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It crawls
+/// the post pages listed in the sitemaps, or in the feed when there is no sitemap, or enumerates `?p=ID` links
+/// when there is neither (cf. `FinderEnumeration`), and yields the media listed by the sitemaps or the feed, then
+/// each page, as JSON arrays of items shaped like WordPress REST API items (see `parse_page`).
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// An asynchronous `futures_core::Stream` of `Result` containing a JSON array `String` if successful, or an
+/// error.
+pub fn get_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+) -> impl Stream<Item = Result<String, Box<dyn std::error::Error>>> + 'a {
+    try_stream! {
+        let mut found = false;
+
+        let permalink_sources = [
+            sitemap::get_items_stream(client, config).boxed_local(),
+            feed::get_items_stream(client, config).boxed_local(),
+        ];
+        // A missing sitemap or feed is not an error, the next source of permalinks is then used.
+        for mut permalinks in permalink_sources {
+            found = true;
+            while let Some(items) = permalinks.next().await {
+                let items = match items {
+                    Ok(items) => items,
+                    Err(e) if e.is::<sitemap::NoSitemap>() || e.is::<feed::NoFeed>() => {
+                        found = false;
+                        break;
+                    }
+                    Err(e) => Err(e)?,
+                };
+                // The media listed by the source (sitemap images and videos, feed enclosures) are candidates
+                // too, even when they are not embedded in the post pages.
+                let media = items
+                    .iter()
+                    .filter(|item| item["source_url"].is_string())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !media.is_empty() {
+                    yield serde_json::Value::Array(media).to_string();
+                }
+                for link in items.iter().filter_map(|item| item["link"].as_str()) {
+                    if let Some(items) = get_page(client, link, &config.retry).await? {
+                        if is_in_range(config, &items) {
+                            yield serde_json::Value::Array(items).to_string();
+                        }
+                    }
+                }
+            }
+            if found {
+                break;
+            }
+        }
+
+        if !found {
+            let enumeration = &config.enumeration;
+            let mut missing = 0;
+            for id in 1..=enumeration.max_id {
+                if missing >= enumeration.max_missing_ids {
+                    break;
+                }
+                if config.exclude.iter().any(|exclude| u32::from(*exclude) == id) {
+                    continue;
+                }
+                match get_post_page(client, config, id).await? {
+                    Some(items) => {
+                        missing = 0;
+                        if is_in_range(config, &items) {
+                            yield serde_json::Value::Array(items).to_string();
+                        }
+                    }
+                    None => missing += 1,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::pin_mut;
+
+    #[test]
+    fn test_meta_tags() {
+        let html = r#"<head>
+            <meta property="og:video" content="http://example.com/a.mp4?a=1&amp;b=2" />
+            <meta content='http://example.com/b.mp4' name='twitter:player:stream'>
+            <meta charset="utf-8">
+        </head>"#;
+
+        assert_eq!(
+            meta_tags(html),
+            vec![
+                (
+                    "og:video".to_string(),
+                    "http://example.com/a.mp4?a=1&b=2".to_string()
+                ),
+                (
+                    "twitter:player:stream".to_string(),
+                    "http://example.com/b.mp4".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_media_objects() {
+        let json = serde_json::json!({"@graph": [
+            {"@type": "WebPage", "contentUrl": "http://example.com/page"},
+            {"@type": ["VideoObject"], "contentUrl": "http://example.com/a.mp4"},
+            {"@type": "Article", "video": {"@type": "VideoObject", "contentUrl": "http://example.com/b.mp4"}},
+            {"@type": "PodcastEpisode", "associatedMedia": {"@type": "AudioObject", "contentUrl": "http://example.com/c.mp3"}}
+        ]});
+
+        let mut urls = vec![];
+        media_objects(&json, &mut urls);
+
+        assert_eq!(
+            urls,
+            vec![
+                "http://example.com/a.mp4",
+                "http://example.com/b.mp4",
+                "http://example.com/c.mp3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_page() {
+        let html = r#"<html><head>
+            <meta property="article:published_time" content="2021-01-05T10:00:00+00:00" />
+            <meta property="og:image" content="http://example.com/wp-content/uploads/2021/01/poster.jpg" />
+            <meta property="og:video:secure_url" content="https://cdn.example.com/clip.mp4" />
+            <script type="application/ld+json">{"@type": "VideoObject", "contentUrl": "https://cdn.example.com/other.mov"}</script>
+        </head><body class="post-template-default single postid-12"></body></html>"#;
+
+        let items = parse_page("http://example.com/hello-world/", html);
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0]["id"], 12);
+        assert_eq!(items[0]["link"], "http://example.com/hello-world/");
+        assert_eq!(items[0]["date"], "2021-01-05T10:00:00");
+        assert_eq!(items[0]["modified"], serde_json::Value::Null);
+        assert_eq!(items[0]["content"]["rendered"], html);
+        assert_eq!(
+            items[0]["_embedded"]["wp:featuredmedia"][0]["source_url"],
+            "http://example.com/wp-content/uploads/2021/01/poster.jpg"
+        );
+        assert_eq!(items[1]["source_url"], "https://cdn.example.com/clip.mp4");
+        assert_eq!(items[2]["source_url"], "https://cdn.example.com/other.mov");
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_id_enumeration() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let post_mock = server
+            .mock("GET", "/?p=1")
+            .with_status(301)
+            .with_header("location", &format!("{url}/hello-world/"))
+            .create_async()
+            .await;
+
+        let permalink_mock = server
+            .mock("GET", "/hello-world/")
+            .with_body(r#"<meta property="og:video" content="http://example.com/a.mp4"><body class="single postid-1">"#)
+            .create_async()
+            .await;
+
+        let excluded_mock = server.mock("GET", "/?p=2").expect(0).create_async().await;
+
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(404)
+            .expect(7 + crate::FinderEnumeration::default().max_missing_ids as usize)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: url.clone(),
+            exclude: vec![2],
+            ..Default::default()
+        };
+
+        let stream = get_stream(&client, &config);
+        pin_mut!(stream);
+
+        let bodies = stream
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
+
+        post_mock.assert_async().await;
+        permalink_mock.assert_async().await;
+        excluded_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains(&format!(r#""link":"{url}/hello-world/""#)));
+        assert!(bodies[0].contains(r#"{"source_url":"http://example.com/a.mp4"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_soft_404s() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let sources_mock = server
+            .mock("GET", mockito::Matcher::Regex("xml|feed".to_string()))
+            .with_status(404)
+            .expect(6)
+            .create_async()
+            .await;
+        // Missing posts redirect to the home page, and other IDs answer a page without post.
+        let home_mock = server
+            .mock("GET", "/?p=1")
+            .with_status(302)
+            .with_header("location", &format!("{url}/"))
+            .create_async()
+            .await;
+        let page_mock = server
+            .mock("GET", mockito::Matcher::Regex(r"^/(\?p=\d+)?$".to_string()))
+            .with_body(r#"<body class="home blog">"#)
+            .expect(4)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url,
+            enumeration: crate::FinderEnumeration {
+                max_id: 1000,
+                max_missing_ids: 4,
+            },
+            ..Default::default()
+        };
+
+        let bodies = get_stream(&client, &config).collect::<Vec<_>>().await;
+
+        sources_mock.assert_async().await;
+        home_mock.assert_async().await;
+        page_mock.assert_async().await;
+        assert!(bodies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_max_id() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let sources_mock = server
+            .mock("GET", mockito::Matcher::Regex("xml|feed".to_string()))
+            .with_status(404)
+            .expect(6)
+            .create_async()
+            .await;
+        let post_mock = server
+            .mock("GET", mockito::Matcher::Regex(r"^/\?p=\d+$".to_string()))
+            .with_body(r#"<body class="single postid-1">"#)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url,
+            enumeration: crate::FinderEnumeration {
+                max_id: 3,
+                max_missing_ids: 100,
+            },
+            ..Default::default()
+        };
+
+        let bodies = get_stream(&client, &config).collect::<Vec<_>>().await;
+
+        sources_mock.assert_async().await;
+        post_mock.assert_async().await;
+        assert_eq!(bodies.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_sitemap() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let sitemap_mock = server
+            .mock("GET", "/wp-sitemap.xml")
+            .with_body(format!(
+                r#"<urlset>
+                    <url>
+                        <loc>{url}/hello-world/</loc>
+                        <video:video><video:content_loc>http://example.com/clip.mp4</video:content_loc></video:video>
+                    </url>
+                </urlset>"#
+            ))
+            .create_async()
+            .await;
+        let permalink_mock = server
+            .mock("GET", "/hello-world/")
+            .with_body(r#"<meta property="og:video" content="http://example.com/a.mp4"><body class="single postid-1">"#)
+            .create_async()
+            .await;
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(404)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: url.clone(),
+            ..Default::default()
+        };
+
+        let bodies = get_stream(&client, &config)
+            .map(|body| body.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        sitemap_mock.assert_async().await;
+        permalink_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+
+        // The video of the sitemap comes first, then the page of the post, without enumerating IDs.
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(
+            bodies[0],
+            r#"[{"source_url":"http://example.com/clip.mp4"}]"#
+        );
+        assert!(bodies[1].contains(&format!(r#""link":"{url}/hello-world/""#)));
+        assert!(bodies[1].contains(r#"{"source_url":"http://example.com/a.mp4"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_sitemap_error() {
+        let client = reqwest::Client::new();
+        let config = FinderConfig::default();
+
+        let bodies = get_stream(&client, &config).collect::<Vec<_>>().await;
+
+        // The error of the sitemap source ends the stream instead of falling back to the feed.
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(
+            bodies[0].as_ref().unwrap_err().to_string(),
+            "URL is required"
+        );
+    }
+}
@@ -1,7 +1,12 @@
 //! # pf_lib
 //!
 //! This crate provides functionality to retrieve existing video URLs from a WordPress websites that use
-//! the REST API. It supports both [`media`] and [`posts`] resources.
+//! the REST API. It supports [`media`], [`posts`], [`pages`] and custom post type resources. Websites that
+//! disable the REST API can be scanned through their sitemaps, their feeds or by crawling their pages instead.
+//! Audio, image and document URLs can be retrieved as well (see [`MimeRegistry::for_kind`]).
+//! Embedders can inject their HTTP clients, probe concurrently and add site-specific [`Extractor`]s and
+//! [`Verifier`]s with [`Finder::builder`]. The candidate URLs can be listed without probing them with
+//! [`find_candidates`], e.g. to tune the heuristics.
 //!
 //! ## Usage
 //!
@@ -31,13 +36,49 @@
 //!
 //! [`media`]: https://developer.wordpress.org/rest-api/reference/media/
 //! [`posts`]: https://developer.wordpress.org/rest-api/reference/posts/
+//! [`pages`]: https://developer.wordpress.org/rest-api/reference/pages/
 
 mod api;
 mod config;
+mod date_utils;
+mod extractor;
+mod feed;
 mod finder;
+mod fixtures;
+mod hooks;
+mod html;
 mod link_utils;
 mod mime_types;
+mod probe;
+mod probe_cache;
+mod response_cache;
+mod seen;
+mod sitemap;
+#[cfg(test)]
+mod test_utils;
 mod url_extractor;
+mod verifier;
 
-pub use config::{FinderConfig, FinderTarget};
-pub use finder::find;
+pub use config::{
+    FinderAttachments, FinderBudget, FinderConfig, FinderEnumeration, FinderRedirects, FinderRetry,
+    FinderSource, FinderTarget, FinderVerification,
+};
+pub use extractor::{
+    Candidate, ContentExtractor, ExtractContext, Extractor, Extractors, SlugExtractor,
+    SourceUrlExtractor,
+};
+pub use finder::{
+    find, find_candidates, find_matches, post_types, Finder, FinderBuilder, FinderCandidate,
+};
+pub use fixtures::Fixtures;
+pub use hooks::FinderHooks;
+pub use link_utils::does_link_exist;
+pub use mime_types::{MediaKind, MimeRegistry};
+pub use probe::{FinderMatch, FinderProbe, ProbeOutcome};
+pub use probe_cache::{ProbeCache, ProbeEntry};
+pub use response_cache::{CachedResponse, ResponseCache};
+pub use seen::SeenUrls;
+pub use url_extractor::{UploadBase, UploadBases};
+pub use verifier::{
+    HeadVerifier, SniffVerifier, TrustVerifier, Verdict, Verifier, Verifiers, VerifyContext,
+};
@@ -1,4 +1,9 @@
-use crate::{mime_types::SUPPORTED_MIME_TYPES, FinderConfig, FinderTarget};
+use crate::mime_types::{self, SNIFF_LENGTH};
+use crate::probe_cache::{ProbeCache, ProbeEntry};
+use crate::{
+    FinderAttachments, FinderConfig, FinderMatch, FinderProbe, FinderRedirects, FinderRetry,
+    FinderTarget, MimeRegistry, ProbeOutcome,
+};
 
 use std::error::Error;
 
@@ -19,10 +24,7 @@ pub fn build_url_from_config(config: &FinderConfig) -> Result<String, Box<dyn Er
     Ok(format!(
         "{}/wp-json/wp/v2/{}?per_page=100{}{}{}{}{}{}",
         config.url,
-        match &config.target {
-            FinderTarget::Posts { .. } => "posts",
-            FinderTarget::Media => "media",
-        },
+        config.target.rest_base(),
         match &config.before {
             Some(value) => format!("&before={value}"),
             None => String::new(),
@@ -57,7 +59,8 @@ pub fn build_url_from_config(config: &FinderConfig) -> Result<String, Box<dyn Er
                 categories_exclude,
                 tags_exclude,
             } => format!(
-                "&_embed=wp:featuredmedia{}{}",
+                "&_embed={}{}{}",
+                embedded_links(config),
                 if !categories_exclude.is_empty() {
                     format!(
                         "&categories_exclude={}",
@@ -83,11 +86,51 @@ pub fn build_url_from_config(config: &FinderConfig) -> Result<String, Box<dyn Er
                     String::new()
                 },
             ),
-            _ => String::new(),
+            FinderTarget::Pages | FinderTarget::Custom { .. } =>
+                format!("&_embed={}", embedded_links(config)),
+            FinderTarget::Media => String::new(),
         }
     ))
 }
 
+/// Returns the links embedded in the pages of posts, pages and custom post types.
+fn embedded_links(config: &FinderConfig) -> &'static str {
+    match config.attachments {
+        FinderAttachments::Embed => "wp:featuredmedia,wp:attachment",
+        FinderAttachments::Skip | FinderAttachments::Parent => "wp:featuredmedia",
+    }
+}
+
+/// Builds the URL of the media attached to the items of a page (cf. `FinderAttachments::Parent`).
+///
+/// # Arguments
+///
+/// * `config` - The `FinderConfig`.
+/// * `body` - The JSON body of the page.
+///
+/// # Returns
+///
+/// An `Option` containing the URL, or `None` if the items are media or if the page has no item.
+pub fn build_attachments_url(config: &FinderConfig, body: &str) -> Option<String> {
+    if config.attachments != FinderAttachments::Parent || config.target == FinderTarget::Media {
+        return None;
+    }
+    let items = serde_json::from_str::<Vec<serde_json::Value>>(body).ok()?;
+    let ids = items
+        .iter()
+        .filter_map(|item| item["id"].as_u64())
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+    if ids.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}/wp-json/wp/v2/media?per_page=100&parent={}",
+        config.url,
+        ids.join(",")
+    ))
+}
+
 /// Extracts the next link from the HTTP headers.
 ///
 /// This function looks for the `link` header in the provided header map and extracts the URL
@@ -114,38 +157,410 @@ pub fn get_next_link_from_headers(headers: &reqwest::header::HeaderMap) -> Optio
         })
 }
 
+/// Sends a request, retrying it according to the `FinderRetry` when it fails transiently.
+///
+/// Connection errors, timeouts, 429 and 5xx statuses are retried, after a delay doubled on each retry.
+///
+/// # Arguments
+///
+/// * `request` - The request to send.
+/// * `retry` - The retry policy.
+///
+/// # Returns
+///
+/// The result of the last attempt.
+pub async fn send_with_retry(
+    request: reqwest::RequestBuilder,
+    retry: &FinderRetry,
+) -> reqwest::Result<reqwest::Response> {
+    let mut attempt = 0;
+    loop {
+        // Requests with streamed bodies cannot be cloned, nor retried.
+        let Some(next) = request.try_clone() else {
+            return request.send().await;
+        };
+        let result = next.send().await;
+        let transient = match &result {
+            Ok(response) => {
+                response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || response.status().is_server_error()
+            }
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+        if !transient || attempt >= retry.max_retries {
+            return result;
+        }
+        tokio::time::sleep(retry.backoff.saturating_mul(2u32.saturating_pow(attempt))).await;
+        attempt += 1;
+    }
+}
+
+/// Returns the URLs with the extension of a URL in lowercase and in uppercase, without the URL itself.
+///
+/// # Arguments
+///
+/// * `url` - The URL.
+///
+/// # Returns
+///
+/// A `Vec` containing the variants, which is empty if the URL has no extension.
+pub fn extension_case_variants(url: &str) -> Vec<String> {
+    let path_end = url.find(['?', '#']).unwrap_or(url.len());
+    let (path, suffix) = url.split_at(path_end);
+    let Some((name, ext)) = path
+        .rsplit_once('.')
+        .filter(|(_, ext)| !ext.is_empty() && !ext.contains('/'))
+    else {
+        return vec![];
+    };
+    let mut variants = vec![];
+    for variant in [ext.to_lowercase(), ext.to_uppercase()] {
+        let variant = format!("{name}.{variant}{suffix}");
+        if variant != url && !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    variants
+}
+
+/// HTTP statuses with which servers and CDNs reject HEAD requests that GET requests pass.
+const HEAD_REJECTED_STATUSES: &[u16] = &[403, 405, 501];
+
+/// Builds the `reqwest::Client` of `probe_link` and `sniff_link`, which follow redirects themselves.
+///
+/// # Returns
+///
+/// A `Result` containing a `reqwest::Client` that does not follow redirects, or an error.
+pub fn probe_client() -> Result<reqwest::Client, Box<dyn Error>> {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}").into())
+}
+
+/// Checks whether a host is one of the placeholder hosts or one of their subdomains.
+fn is_placeholder_host(host: &str, placeholder_hosts: &[String]) -> bool {
+    placeholder_hosts.iter().any(|placeholder| {
+        host.eq_ignore_ascii_case(placeholder)
+            || host
+                .to_lowercase()
+                .ends_with(&format!(".{}", placeholder.to_lowercase()))
+    })
+}
+
+/// Sends a request and follows its redirects according to the `FinderRedirects`, retrying each hop according to
+/// the `FinderRetry` (cf. `send_with_retry`).
+///
+/// # Arguments
+///
+/// * `client` - A `reqwest::Client` that does not follow redirects (cf. `probe_client`).
+/// * `method` - The HTTP method of the request.
+/// * `url` - The URL of the request.
+/// * `range` - The optional `Range` header of the request.
+/// * `redirects` - The redirect policy.
+/// * `retry` - The retry policy.
+///
+/// # Returns
+///
+/// An `Option` containing the last response and the redirect chain (the URLs redirected to, the last one being the
+/// final URL), or `None` if the request fails, if the policy forbids a redirect or if the final host is a
+/// placeholder host.
+async fn send_following_redirects(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    url: &str,
+    range: Option<&str>,
+    redirects: &FinderRedirects,
+    retry: &FinderRetry,
+) -> Option<(reqwest::Response, Vec<String>)> {
+    let origin = reqwest::Url::parse(url).ok()?;
+    let mut current = origin.clone();
+    let mut chain = vec![];
+    loop {
+        let mut request = client.request(method.clone(), current.clone());
+        if let Some(range) = range {
+            request = request.header(reqwest::header::RANGE, range);
+        }
+        let response = send_with_retry(request, retry).await.ok()?;
+
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        match location {
+            Some(location) if response.status().is_redirection() => {
+                let next = current.join(location).ok()?;
+                if chain.len() >= redirects.max_hops
+                    || (redirects.same_host_only && next.host_str() != origin.host_str())
+                {
+                    return None;
+                }
+                chain.push(next.to_string());
+                current = next;
+            }
+            _ => {
+                if is_placeholder_host(
+                    current.host_str().unwrap_or_default(),
+                    &redirects.placeholder_hosts,
+                ) {
+                    return None;
+                }
+                return Some((response, chain));
+            }
+        }
+    }
+}
+
+/// Tells whether the response of a probe request that did not confirm a URL proves it absent, in which case a
+/// missing URL is stored in the cache.
+///
+/// # Returns
+///
+/// `ProbeOutcome::Absent` if the URL is of another type or missing (cf. `ProbeEntry::is_cacheable`), or
+/// `ProbeOutcome::Unknown` for other error statuses.
+fn miss(cache: Option<&ProbeCache>, url: &str, response: &reqwest::Response) -> ProbeOutcome {
+    if response.status().is_success() {
+        return ProbeOutcome::Absent;
+    }
+    let entry = ProbeEntry::from_response(response);
+    if !entry.is_cacheable() {
+        return ProbeOutcome::Unknown;
+    }
+    if let Some(cache) = cache {
+        // A cache that cannot be written only costs a probe request on the next scan.
+        let _ = cache.insert(url, entry);
+    }
+    ProbeOutcome::Absent
+}
+
+/// Builds the `FinderMatch` of a probed URL.
+fn to_match(url: &str, mime_type: &str, probe: FinderProbe, redirects: Vec<String>) -> FinderMatch {
+    FinderMatch {
+        url: url.to_string(),
+        mime_type: mime_type.to_string(),
+        probe,
+        final_url: redirects.last().map_or(url, |last| last).to_string(),
+        redirects,
+    }
+}
+
+/// Probes a link by sending a HEAD request.
+///
+/// This function sends a HEAD request to the given URL, follows its redirects according to the
+/// `FinderRedirects`, and checks if the response status indicates success and if the content type
+/// matches one of the registered MIME types (cf. `MimeRegistry::match_content_type`). When the
+/// server rejects the HEAD request with a 403, 405 or 501 status, the request can be retried as a
+/// GET request of a single byte. Requests that fail transiently are retried according to the
+/// `FinderRetry`. URLs that the `ProbeCache` knows as missing are not requested again, and URLs
+/// that turn out to be missing are stored in it.
+///
+/// # Arguments
+///
+/// * `client` - A `reqwest::Client` that does not follow redirects (cf. `probe_client`).
+/// * `url` - The URL to check.
+/// * `mime_types` - The MIME types to accept.
+/// * `redirects` - The redirect policy.
+/// * `retry` - The retry policy.
+/// * `get_fallback` - Whether to retry rejected HEAD requests as GET requests.
+/// * `cache` - The optional `ProbeCache`.
+///
+/// # Returns
+///
+/// The `ProbeOutcome` of the link: `Found` with its `FinderMatch`, `Absent` if it does not exist or has another
+/// content type, or `Unknown` if the probe could not tell.
+pub async fn probe_link(
+    client: &reqwest::Client,
+    url: &str,
+    mime_types: &MimeRegistry,
+    redirects: &FinderRedirects,
+    retry: &FinderRetry,
+    get_fallback: bool,
+    cache: Option<&ProbeCache>,
+) -> ProbeOutcome {
+    if cache.is_some_and(|cache| cache.get(url).is_some()) {
+        return ProbeOutcome::Unknown;
+    }
+    let Some(mut probed) =
+        send_following_redirects(client, reqwest::Method::HEAD, url, None, redirects, retry).await
+    else {
+        return ProbeOutcome::Unknown;
+    };
+    let mut probe = FinderProbe::Head;
+    if get_fallback && HEAD_REJECTED_STATUSES.contains(&probed.0.status().as_u16()) {
+        // Servers that ignore the range send the whole file, whose body is never read.
+        let Some(retried) = send_following_redirects(
+            client,
+            reqwest::Method::GET,
+            url,
+            Some("bytes=0-0"),
+            redirects,
+            retry,
+        )
+        .await
+        else {
+            return ProbeOutcome::Unknown;
+        };
+        probed = retried;
+        probe = FinderProbe::RangeGet;
+    }
+    let (response, chain) = probed;
+    let found = response
+        .status()
+        .is_success()
+        .then(|| response.headers().get("content-type")?.to_str().ok())
+        .flatten()
+        .and_then(|content_type| mime_types.match_content_type(content_type, url));
+    match found {
+        Some(mime) => ProbeOutcome::Found(to_match(url, mime, probe, chain)),
+        None => miss(cache, url, &response),
+    }
+}
+
 /// Checks if a link exists by sending a HEAD request.
 ///
-/// This function sends a HEAD request to the given URL and checks if the response status
-/// indicates success.
+/// This function is a wrapper of `probe_link` that accepts the MIME types of all the enabled features (cf.
+/// `SUPPORTED_MIME_TYPES`) with the default `FinderRedirects` and `FinderRetry`, without retrying rejected HEAD
+/// requests. It does not use a `ProbeCache` either, which belongs to a scan (cf. `FinderConfig::probe_cache`):
+/// callers that check many links can verify them with a `HeadVerifier` and a `FinderConfig` that has one instead.
 ///
 /// # Arguments
 ///
-/// * `client` - A reference to the `reqwest::blocking::Client` used to send the request.
+/// * `client` - A `reqwest::Client`.
 /// * `url` - The URL to check.
 ///
 /// # Returns
 ///
-/// `true` if the link exists (i.e., the response status is successful), `false` otherwise.
+/// A boolean indicating whether the link exists and has a supported content type.
 pub async fn does_link_exist(client: &reqwest::Client, url: &str) -> bool {
-    match client.head(url).send().await {
-        Ok(response) => {
-            response.status().is_success()
-                && response.headers().get("content-type").is_some_and(|v| {
-                    SUPPORTED_MIME_TYPES
-                        .iter()
-                        .map(|(_, mime)| *mime)
-                        .collect::<Vec<_>>()
-                        .contains(&v.to_str().unwrap_or_default())
-                })
+    let mime_types = mime_types::SUPPORTED_MIME_TYPES.iter().copied().collect();
+    probe_link(
+        client,
+        url,
+        &mime_types,
+        &FinderRedirects::default(),
+        &FinderRetry::default(),
+        false,
+        None,
+    )
+    .await
+    .found()
+    .is_some()
+}
+
+/// Probes a link by sniffing its leading bytes.
+///
+/// This function sends a GET request with a `Range: bytes=0-4095` header, which also works on servers that reject
+/// HEAD requests, follows its redirects according to the `FinderRedirects`, reads at most `SNIFF_LENGTH` bytes and
+/// sniffs their format (cf. `mime_types::sniff`). Unlike the `Content-Type` header, the magic bytes are not fooled
+/// by soft-404 pages or hotlink protection redirects. Like `probe_link`, it retries requests that fail transiently
+/// and skips and stores missing URLs in the `ProbeCache`.
+///
+/// # Arguments
+///
+/// * `client` - A `reqwest::Client` that does not follow redirects (cf. `probe_client`).
+/// * `url` - The URL to check.
+/// * `mime_types` - The MIME types to accept.
+/// * `redirects` - The redirect policy.
+/// * `retry` - The retry policy.
+/// * `cache` - The optional `ProbeCache`.
+///
+/// # Returns
+///
+/// A `Result` containing the `ProbeOutcome` of the link (`Absent` if it does not exist or is neither declared nor
+/// sniffed as a registered MIME type), or an error if the link is declared (by its `Content-Type` header or its
+/// extension) as a registered MIME type that its content does not match.
+pub async fn sniff_link(
+    client: &reqwest::Client,
+    url: &str,
+    mime_types: &MimeRegistry,
+    redirects: &FinderRedirects,
+    retry: &FinderRetry,
+    cache: Option<&ProbeCache>,
+) -> Result<ProbeOutcome, Box<dyn Error>> {
+    if cache.is_some_and(|cache| cache.get(url).is_some()) {
+        return Ok(ProbeOutcome::Unknown);
+    }
+    let range = format!("bytes=0-{}", SNIFF_LENGTH - 1);
+    let Some((mut response, chain)) = send_following_redirects(
+        client,
+        reqwest::Method::GET,
+        url,
+        Some(&range),
+        redirects,
+        retry,
+    )
+    .await
+    else {
+        return Ok(ProbeOutcome::Unknown);
+    };
+    if !response.status().is_success() {
+        return Ok(miss(cache, url, &response));
+    }
+
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    // Servers that ignore the range send the whole file, only its leading bytes are read.
+    let mut bytes = Vec::with_capacity(SNIFF_LENGTH);
+    while bytes.len() < SNIFF_LENGTH {
+        match response.chunk().await {
+            Ok(Some(chunk)) => bytes.extend_from_slice(&chunk),
+            _ => break,
         }
-        Err(_) => false,
+    }
+    bytes.truncate(SNIFF_LENGTH);
+
+    let declared = mime_types
+        .match_content_type(&content_type, url)
+        .or(mime_types.get_by_url(url));
+    let sniffed = mime_types::sniff(&bytes);
+
+    let registered = sniffed
+        .iter()
+        .copied()
+        .filter(|mime| mime_types.contains_mime(mime))
+        .collect::<Vec<_>>();
+    if let Some(mime) = declared
+        .filter(|declared| registered.contains(declared))
+        .or(registered.first().copied())
+    {
+        return Ok(ProbeOutcome::Found(to_match(
+            url,
+            mime,
+            FinderProbe::Sniff,
+            chain,
+        )));
+    }
+
+    match declared {
+        Some(declared) => Err(format!(
+            "Content mismatch for {url}: declared as {declared}, sniffed as {}",
+            sniffed.first().map_or_else(
+                || match mime_types::parse_content_type(&content_type) {
+                    Some(essence) => format!("unknown content ({essence})"),
+                    None => "unknown content".to_string(),
+                },
+                |mime| mime.to_string()
+            )
+        )
+        .into()),
+        None => Ok(miss(cache, url, &response)),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::TempDir;
+
+    use crate::{
+        Extractors, FinderBudget, FinderEnumeration, FinderHooks, FinderSource, FinderVerification,
+        SeenUrls, UploadBases, Verifiers,
+    };
 
     #[test]
     fn test_build_url_from_config() {
@@ -159,6 +574,24 @@ mod tests {
                 categories_exclude: vec![4, 5, 6],
                 tags_exclude: vec![7, 8, 9],
             },
+            source: FinderSource::Api,
+            attachments: FinderAttachments::Skip,
+            mime_types: MimeRegistry::default(),
+            extractors: Extractors::default(),
+            budget: FinderBudget::default(),
+            retry: FinderRetry::default(),
+            enumeration: FinderEnumeration::default(),
+            verifiers: Verifiers::default(),
+            verification: FinderVerification::Head,
+            redirects: FinderRedirects::default(),
+            hooks: FinderHooks::default(),
+            seen: SeenUrls::default(),
+            listed: SeenUrls::default(),
+            upload_bases: UploadBases::default(),
+            probe_cache: None,
+            response_cache: None,
+            offline: false,
+            fixtures: None,
             url: "http://example.com".to_string(),
         };
 
@@ -201,6 +634,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_url_from_config_with_embedded_attachments() {
+        let config = FinderConfig {
+            url: "http://example.com".to_string(),
+            target: FinderTarget::Pages,
+            attachments: FinderAttachments::Embed,
+            ..Default::default()
+        };
+
+        let url = build_url_from_config(&config).unwrap();
+
+        assert_eq!(
+            url,
+            "http://example.com/wp-json/wp/v2/pages?per_page=100&_embed=wp:featuredmedia,wp:attachment"
+        );
+    }
+
+    #[test]
+    fn test_build_attachments_url() {
+        let mut config = FinderConfig {
+            url: "http://example.com".to_string(),
+            target: FinderTarget::Pages,
+            attachments: FinderAttachments::Parent,
+            ..Default::default()
+        };
+        let body = r#"[{"id": 1}, {"id": 22}, {"slug": "no-id"}]"#;
+
+        assert_eq!(
+            build_attachments_url(&config, body).unwrap(),
+            "http://example.com/wp-json/wp/v2/media?per_page=100&parent=1,22"
+        );
+        assert_eq!(build_attachments_url(&config, "[]"), None);
+        assert_eq!(build_attachments_url(&config, "not json"), None);
+
+        config.target = FinderTarget::Media;
+        assert_eq!(build_attachments_url(&config, body), None);
+        config.target = FinderTarget::Pages;
+        config.attachments = FinderAttachments::Embed;
+        assert_eq!(build_attachments_url(&config, body), None);
+    }
+
+    #[test]
+    fn test_build_url_from_config_with_pages_target() {
+        let config = FinderConfig {
+            url: "http://example.com".to_string(),
+            target: FinderTarget::Pages,
+            exclude: vec![1],
+            ..Default::default()
+        };
+
+        let url = build_url_from_config(&config).unwrap();
+
+        assert_eq!(
+            url,
+            "http://example.com/wp-json/wp/v2/pages?per_page=100&exclude=1&_embed=wp:featuredmedia"
+        );
+    }
+
+    #[test]
+    fn test_build_url_from_config_with_custom_target() {
+        let config = FinderConfig {
+            url: "http://example.com".to_string(),
+            target: FinderTarget::Custom {
+                rest_base: "product".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let url = build_url_from_config(&config).unwrap();
+
+        assert_eq!(
+            url,
+            "http://example.com/wp-json/wp/v2/product?per_page=100&_embed=wp:featuredmedia"
+        );
+    }
+
     #[test]
     fn test_build_url_from_config_with_invalid_config() {
         let config = FinderConfig {
@@ -246,6 +755,79 @@ mod tests {
         assert_eq!(next_link, None);
     }
 
+    #[test]
+    fn test_extension_case_variants() {
+        assert_eq!(
+            extension_case_variants("http://example.com/Video-Final.Mp4"),
+            [
+                "http://example.com/Video-Final.mp4",
+                "http://example.com/Video-Final.MP4"
+            ]
+        );
+        assert_eq!(
+            extension_case_variants("http://example.com/clip.mp4?ver=2"),
+            ["http://example.com/clip.MP4?ver=2"]
+        );
+        assert!(extension_case_variants("http://example.com/v1.2/clip").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable_mock = server
+            .mock("GET", "/wp-json/wp/v2/media")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+        let client = reqwest::Client::new();
+        let retry = FinderRetry {
+            max_retries: 2,
+            backoff: std::time::Duration::from_millis(1),
+        };
+
+        let response = send_with_retry(
+            client.get(format!("{}/wp-json/wp/v2/media", server.url())),
+            &retry,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), 503);
+        unavailable_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_probe_link_with_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+        let client = reqwest::Client::new();
+        let retry = FinderRetry {
+            max_retries: 2,
+            backoff: std::time::Duration::from_millis(1),
+        };
+
+        let outcome = probe_link(
+            &client,
+            &format!("{}/video.mp4", server.url()),
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &retry,
+            false,
+            None,
+        )
+        .await;
+
+        assert_eq!(outcome, ProbeOutcome::Unknown);
+        unavailable_mock.assert_async().await;
+    }
+
+    #[cfg(feature = "mp4")]
     #[tokio::test]
     async fn test_does_link_exist() {
         let mut server = mockito::Server::new_async().await;
@@ -284,6 +866,464 @@ mod tests {
         assert!(!exists);
     }
 
+    #[tokio::test]
+    async fn test_probe_link_with_custom_mime_types() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("HEAD", "/")
+            .with_header("Content-Type", "video/x-matroska")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = server.url();
+
+        let exists_with_default = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+        let exists_with_mkv = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::from_iter([("mkv", "video/x-matroska")]),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(exists_with_default, ProbeOutcome::Absent);
+        assert!(exists_with_mkv.found().is_some());
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_probe_link_with_lenient_content_type() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_header("Content-Type", "Video/MP4; charset=binary")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mp4", server.url());
+
+        let mime = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(
+            mime.found().map(|found| (found.mime_type, found.probe)),
+            Some(("video/mp4".to_string(), FinderProbe::Head))
+        );
+    }
+
+    #[cfg(feature = "mov")]
+    #[tokio::test]
+    async fn test_probe_link_with_octet_stream() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("HEAD", "/video.mov")
+            .with_header("Content-Type", "application/octet-stream")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mov", server.url());
+
+        let strict = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+        let lenient = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default().with_octet_stream(true),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(strict, ProbeOutcome::Absent);
+        assert_eq!(
+            lenient.found().map(|found| (found.mime_type, found.probe)),
+            Some(("video/quicktime".to_string(), FinderProbe::Head))
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_probe_link_with_get_fallback() {
+        let mut server = mockito::Server::new_async().await;
+
+        let head_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_status(405)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let get_mock = server
+            .mock("GET", "/video.mp4")
+            .match_header("range", "bytes=0-0")
+            .with_status(206)
+            .with_header("Content-Type", "video/mp4")
+            .with_body("\x00")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mp4", server.url());
+
+        let with_fallback = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+        let without_fallback = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            false,
+            None,
+        )
+        .await;
+
+        head_mock.assert_async().await;
+        get_mock.assert_async().await;
+        assert_eq!(
+            with_fallback
+                .found()
+                .map(|found| (found.mime_type, found.probe)),
+            Some(("video/mp4".to_string(), FinderProbe::RangeGet))
+        );
+        assert_eq!(without_fallback, ProbeOutcome::Unknown);
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_probe_link_with_redirects() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let redirect_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_status(302)
+            .with_header("location", "/signed/video.mp4?token=1")
+            .create_async()
+            .await;
+
+        let signed_mock = server
+            .mock("HEAD", "/signed/video.mp4?token=1")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+
+        let client = probe_client().unwrap();
+        let found = probe_link(
+            &client,
+            &format!("{url}/video.mp4"),
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await
+        .found()
+        .unwrap();
+
+        redirect_mock.assert_async().await;
+        signed_mock.assert_async().await;
+        assert_eq!(found.url, format!("{url}/video.mp4"));
+        assert_eq!(found.final_url, format!("{url}/signed/video.mp4?token=1"));
+        assert_eq!(found.redirects, vec![found.final_url.clone()]);
+
+        let no_redirects = FinderRedirects {
+            max_hops: 0,
+            ..Default::default()
+        };
+        let found = probe_link(
+            &client,
+            &format!("{url}/video.mp4"),
+            &MimeRegistry::default(),
+            &no_redirects,
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        assert_eq!(found, ProbeOutcome::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_probe_link_with_cross_host_and_placeholder_redirects() {
+        let mut server = mockito::Server::new_async().await;
+
+        let redirect_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_status(302)
+            .with_header("location", "http://localhost:1/video.mp4")
+            .create_async()
+            .await;
+
+        let client = probe_client().unwrap();
+        let url = format!("{}/video.mp4", server.url());
+        let same_host_only = FinderRedirects {
+            same_host_only: true,
+            ..Default::default()
+        };
+        let found = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &same_host_only,
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        redirect_mock.assert_async().await;
+        assert_eq!(found, ProbeOutcome::Unknown);
+
+        let placeholder_mock = server
+            .mock("HEAD", "/hotlink.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+
+        let url = format!("{}/hotlink.mp4", server.url());
+        let placeholder = FinderRedirects {
+            placeholder_hosts: vec!["127.0.0.1".to_string()],
+            ..Default::default()
+        };
+        let found = probe_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &placeholder,
+            &FinderRetry::default(),
+            true,
+            None,
+        )
+        .await;
+
+        placeholder_mock.assert_async().await;
+        assert_eq!(found, ProbeOutcome::Unknown);
+    }
+
+    #[test]
+    fn test_is_placeholder_host() {
+        let hosts = vec!["placehold.co".to_string()];
+
+        assert!(is_placeholder_host("placehold.co", &hosts));
+        assert!(is_placeholder_host("CDN.Placehold.co", &hosts));
+        assert!(!is_placeholder_host("notplacehold.co", &hosts));
+    }
+
+    #[tokio::test]
+    async fn test_probe_link_with_cache() {
+        let mut server = mockito::Server::new_async().await;
+
+        let missing_mock = server
+            .mock("HEAD", "/missing.mp4")
+            .with_status(404)
+            .with_header("ETag", "\"abc\"")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let unavailable_mock = server
+            .mock("HEAD", "/unavailable.mp4")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+
+        // Neither a HEAD request rejected without GET fallback nor a file of another type proves a URL missing.
+        let forbidden_mock = server
+            .mock("HEAD", "/forbidden.mp4")
+            .with_status(403)
+            .expect(2)
+            .create_async()
+            .await;
+        let image_mock = server
+            .mock("HEAD", "/image.mp4")
+            .with_header("Content-Type", "image/jpeg")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new("link_cache");
+        let cache = ProbeCache::open(&dir, std::time::Duration::from_secs(3600)).unwrap();
+        let client = reqwest::Client::new();
+
+        // Cached URLs are skipped, without telling whether they exist.
+        for (path, expected) in [
+            ("missing", ProbeOutcome::Absent),
+            ("missing", ProbeOutcome::Unknown),
+            ("unavailable", ProbeOutcome::Unknown),
+            ("unavailable", ProbeOutcome::Unknown),
+            ("forbidden", ProbeOutcome::Unknown),
+            ("forbidden", ProbeOutcome::Unknown),
+            ("image", ProbeOutcome::Absent),
+            ("image", ProbeOutcome::Absent),
+        ] {
+            let outcome = probe_link(
+                &client,
+                &format!("{}/{path}.mp4", server.url()),
+                &MimeRegistry::default(),
+                &FinderRedirects::default(),
+                &FinderRetry::default(),
+                false,
+                Some(&cache),
+            )
+            .await;
+            assert_eq!(outcome, expected, "{path}");
+        }
+
+        missing_mock.assert_async().await;
+        unavailable_mock.assert_async().await;
+        forbidden_mock.assert_async().await;
+        image_mock.assert_async().await;
+        let entry = cache.get(&format!("{}/missing.mp4", server.url())).unwrap();
+        assert_eq!(entry.status, 404);
+        assert_eq!(entry.etag, Some("\"abc\"".to_string()));
+    }
+
+    #[cfg(feature = "mov")]
+    #[tokio::test]
+    async fn test_sniff_link() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/video.mov")
+            .match_header("range", "bytes=0-4095")
+            .with_status(206)
+            .with_header("Content-Type", "application/octet-stream")
+            .with_body(b"\x00\x00\x00\x14ftypqt  \x00\x00\x00\x00")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mov", server.url());
+
+        let mime = sniff_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(
+            mime.unwrap().found().map(|found| found.mime_type),
+            Some("video/quicktime".to_string())
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_sniff_link_with_soft_404() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/video.mp4")
+            .with_header("Content-Type", "text/html; charset=UTF-8")
+            .with_body("<!DOCTYPE html><html><body>Not found</body></html>")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mp4", server.url());
+
+        let mime = sniff_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(
+            mime.unwrap_err().to_string(),
+            format!("Content mismatch for {url}: declared as video/mp4, sniffed as unknown content (text/html)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sniff_link_when_url_does_not_exist() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/video.mp4")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/video.mp4", server.url());
+
+        let mime = sniff_link(
+            &client,
+            &url,
+            &MimeRegistry::default(),
+            &FinderRedirects::default(),
+            &FinderRetry::default(),
+            None,
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert_eq!(mime.unwrap(), ProbeOutcome::Absent);
+    }
+
     #[tokio::test]
     async fn test_does_link_exist_when_url_does_not_exist() {
         let mut server = mockito::Server::new_async().await;
@@ -1,3 +1,29 @@
+/// Kind of media to look for.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MediaKind {
+    /// `video/*` MIME types.
+    #[default]
+    Video,
+    /// `audio/*` MIME types.
+    Audio,
+    /// `image/*` MIME types.
+    Image,
+    /// Other MIME types (e.g. `application/pdf`).
+    Document,
+}
+
+impl MediaKind {
+    /// Returns the kind of a MIME type.
+    pub fn of(mime: &str) -> Self {
+        match mime.split_once('/').map(|(kind, _)| kind) {
+            Some("video") => Self::Video,
+            Some("audio") => Self::Audio,
+            Some("image") => Self::Image,
+            _ => Self::Document,
+        }
+    }
+}
+
 /// Supported MIME types for scraping. Each tuple contains a file extension and its corresponding MIME type.
 pub const SUPPORTED_MIME_TYPES: &[(&str, &str)] = &[
     #[cfg(feature = "mp4")]
@@ -14,4 +40,477 @@ pub const SUPPORTED_MIME_TYPES: &[(&str, &str)] = &[
     ("webm", "video/webm"),
     #[cfg(feature = "wmv")]
     ("wmv", "video/x-ms-wmv"),
+    #[cfg(feature = "mp3")]
+    ("mp3", "audio/mpeg"),
+    #[cfg(feature = "m4a")]
+    ("m4a", "audio/mp4"),
+    #[cfg(feature = "ogg")]
+    ("ogg", "audio/ogg"),
+    #[cfg(feature = "wav")]
+    ("wav", "audio/x-wav"),
+    #[cfg(feature = "jpg")]
+    ("jpg", "image/jpeg"),
+    #[cfg(feature = "jpg")]
+    ("jpeg", "image/jpeg"),
+    #[cfg(feature = "png")]
+    ("png", "image/png"),
+    #[cfg(feature = "gif")]
+    ("gif", "image/gif"),
+    #[cfg(feature = "webp")]
+    ("webp", "image/webp"),
+    #[cfg(feature = "pdf")]
+    ("pdf", "application/pdf"),
 ];
+
+/// Known MIME types, whether or not their feature is enabled. Each tuple contains a file extension and its
+/// corresponding MIME type.
+pub const KNOWN_MIME_TYPES: &[(&str, &str)] = &[
+    ("3gp", "video/3gpp"),
+    ("avi", "video/x-msvideo"),
+    ("flv", "video/x-flv"),
+    ("m4v", "video/x-m4v"),
+    ("mkv", "video/x-matroska"),
+    ("mov", "video/quicktime"),
+    ("mp4", "video/mp4"),
+    ("mpeg", "video/mpeg"),
+    ("mpg", "video/mpeg"),
+    ("ogv", "video/ogg"),
+    ("webm", "video/webm"),
+    ("wmv", "video/x-ms-wmv"),
+    ("aac", "audio/aac"),
+    ("flac", "audio/flac"),
+    ("m4a", "audio/mp4"),
+    ("mp3", "audio/mpeg"),
+    ("oga", "audio/ogg"),
+    ("ogg", "audio/ogg"),
+    ("opus", "audio/opus"),
+    ("wav", "audio/x-wav"),
+    ("avif", "image/avif"),
+    ("gif", "image/gif"),
+    ("heic", "image/heic"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("webp", "image/webp"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("epub", "application/epub+zip"),
+    ("pdf", "application/pdf"),
+    ("ppt", "application/vnd.ms-powerpoint"),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("xls", "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("zip", "application/zip"),
+];
+
+/// Other MIME types that servers send for some formats. Each tuple contains an alias and the MIME type it stands for.
+const MIME_TYPE_ALIASES: &[(&str, &str)] = &[
+    ("video/avi", "video/x-msvideo"),
+    ("audio/x-m4a", "audio/mp4"),
+    ("audio/wav", "audio/x-wav"),
+];
+
+/// Checks whether two MIME types are the same or aliases of each other (cf. `MIME_TYPE_ALIASES`).
+fn is_same_mime(a: &str, b: &str) -> bool {
+    a == b
+        || MIME_TYPE_ALIASES
+            .iter()
+            .any(|&(alias, mime)| (alias, mime) == (a, b) || (alias, mime) == (b, a))
+}
+
+/// Generic MIME types that servers send for files they do not know.
+const OCTET_STREAM_MIME_TYPES: &[&str] = &["application/octet-stream", "binary/octet-stream"];
+
+/// Parses a `Content-Type` header value into its lowercase `type/subtype` essence, dropping parameters such as
+/// `charset`.
+///
+/// # Returns
+///
+/// An `Option` containing the essence, or `None` if the value is not a MIME type.
+pub fn parse_content_type(content_type: &str) -> Option<String> {
+    let essence = content_type.split(';').next()?.trim().to_lowercase();
+    match essence.split_once('/') {
+        Some((kind, subtype))
+            if !kind.is_empty() && !subtype.is_empty() && !subtype.contains('/') =>
+        {
+            Some(essence)
+        }
+        _ => None,
+    }
+}
+
+/// Number of leading bytes needed by `sniff`.
+pub const SNIFF_LENGTH: usize = 4096;
+
+/// Sniffs the container or file format of the leading bytes of a file from its magic bytes.
+///
+/// ISO BMFF (`ftyp`), RIFF (AVI, WAVE, WebP), FLV, EBML (WebM, Matroska), MPEG-PS, MPEG-TS, ASF, Ogg, MP3, JPEG, PNG,
+/// GIF and PDF signatures are recognized.
+///
+/// # Arguments
+///
+/// * `bytes` - The leading bytes of the file, ideally `SNIFF_LENGTH` of them.
+///
+/// # Returns
+///
+/// The MIME types the format is served as (e.g. `video/mp4` and `video/quicktime` for ISO BMFF), or an empty slice
+/// if the format is not recognized.
+pub fn sniff(bytes: &[u8]) -> &'static [&'static str] {
+    const ASF_GUID: &[u8] = &[
+        0x30, 0x26, 0xb2, 0x75, 0x8e, 0x66, 0xcf, 0x11, 0xa6, 0xd9, 0x00, 0xaa, 0x00, 0x62, 0xce,
+        0x6c,
+    ];
+    const TS_PACKET_LENGTH: usize = 188;
+
+    let is_ts = bytes.len() > TS_PACKET_LENGTH * 2
+        && (0..3).all(|packet| bytes[packet * TS_PACKET_LENGTH] == 0x47);
+    let is_mp3_frame = bytes.len() > 1 && bytes[0] == 0xff && bytes[1] & 0xe0 == 0xe0;
+
+    match bytes {
+        [_, _, _, _, b'f', b't', b'y', b'p', b'M', b'4', b'A', b' ', ..] => {
+            &["audio/mp4", "audio/x-m4a"]
+        }
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => &[
+            "video/mp4",
+            "video/quicktime",
+            "video/x-m4v",
+            "video/3gpp",
+            "audio/mp4",
+        ],
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'A', b'V', b'I', b' ', ..] => {
+            &["video/x-msvideo", "video/avi"]
+        }
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => {
+            &["audio/x-wav", "audio/wav"]
+        }
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => &["image/webp"],
+        [b'F', b'L', b'V', 0x01, ..] => &["video/x-flv"],
+        [0x1a, 0x45, 0xdf, 0xa3, ..] => &["video/webm", "video/x-matroska", "audio/webm"],
+        [0x00, 0x00, 0x01, 0xba | 0xb3, ..] => &["video/mpeg"],
+        _ if is_ts => &["video/mp2t", "video/mpeg"],
+        _ if bytes.starts_with(ASF_GUID) => &["video/x-ms-wmv", "video/x-ms-asf", "audio/x-ms-wma"],
+        [b'O', b'g', b'g', b'S', ..] => &["video/ogg", "audio/ogg"],
+        [b'I', b'D', b'3', ..] => &["audio/mpeg"],
+        _ if is_mp3_frame => &["audio/mpeg"],
+        [0xff, 0xd8, 0xff, ..] => &["image/jpeg"],
+        [0x89, b'P', b'N', b'G', ..] => &["image/png"],
+        [b'G', b'I', b'F', b'8', ..] => &["image/gif"],
+        [b'%', b'P', b'D', b'F', b'-', ..] => &["application/pdf"],
+        _ => &[],
+    }
+}
+
+/// Returns the lowercase extension of the last path segment of a URL, ignoring the query and fragment.
+fn url_extension(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let name = path.rsplit('/').next()?;
+    name.rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .filter(|ext| !ext.is_empty())
+}
+
+/// Registry of the MIME types to look for.
+///
+/// Defaults to the video types of the enabled features (cf. `SUPPORTED_MIME_TYPES`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MimeRegistry {
+    types: Vec<(String, String)>,
+    octet_stream: bool,
+}
+
+impl Default for MimeRegistry {
+    fn default() -> Self {
+        Self::for_kind(MediaKind::default())
+    }
+}
+
+impl<E: Into<String>, M: Into<String>> FromIterator<(E, M)> for MimeRegistry {
+    fn from_iter<T: IntoIterator<Item = (E, M)>>(iter: T) -> Self {
+        let mut registry = Self::empty();
+        for (ext, mime) in iter {
+            registry.insert(ext, mime);
+        }
+        registry
+    }
+}
+
+impl MimeRegistry {
+    /// Creates a registry without any MIME type.
+    pub fn empty() -> Self {
+        Self {
+            types: vec![],
+            octet_stream: false,
+        }
+    }
+
+    /// Accepts files served as `application/octet-stream` or `binary/octet-stream` when the extension of their URL
+    /// is registered.
+    pub fn with_octet_stream(mut self, octet_stream: bool) -> Self {
+        self.octet_stream = octet_stream;
+        self
+    }
+
+    /// Creates a registry with the types of the enabled features (cf. `SUPPORTED_MIME_TYPES`) of a kind.
+    pub fn for_kind(kind: MediaKind) -> Self {
+        SUPPORTED_MIME_TYPES
+            .iter()
+            .filter(|(_, mime)| MediaKind::of(mime) == kind)
+            .copied()
+            .collect()
+    }
+
+    /// Returns the MIME type of a known extension (cf. `KNOWN_MIME_TYPES`).
+    pub fn known(ext: &str) -> Option<&'static str> {
+        KNOWN_MIME_TYPES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+            .map(|(_, mime)| *mime)
+    }
+
+    /// Adds an extension and its MIME type, replacing the MIME type of an already registered extension.
+    pub fn insert(&mut self, ext: impl Into<String>, mime: impl Into<String>) {
+        let ext = ext.into().to_lowercase();
+        let mime = mime.into().to_lowercase();
+        match self.types.iter_mut().find(|(e, _)| *e == ext) {
+            Some(entry) => entry.1 = mime,
+            None => self.types.push((ext, mime)),
+        }
+    }
+
+    /// Removes an extension.
+    pub fn remove(&mut self, ext: &str) {
+        self.types.retain(|(e, _)| !e.eq_ignore_ascii_case(ext));
+    }
+
+    /// Returns the `(extension, MIME type)` pairs of the registry.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.types
+            .iter()
+            .map(|(ext, mime)| (ext.as_str(), mime.as_str()))
+    }
+
+    /// Returns the registered extensions.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|(ext, _)| ext)
+    }
+
+    /// Checks whether an extension is registered.
+    pub fn contains_extension(&self, ext: &str) -> bool {
+        self.extensions().any(|e| e.eq_ignore_ascii_case(ext))
+    }
+
+    /// Checks whether a MIME type is registered.
+    pub fn contains_mime(&self, mime: &str) -> bool {
+        self.iter().any(|(_, m)| m == mime)
+    }
+
+    /// Matches the `Content-Type` header value of a URL against the registry.
+    ///
+    /// The value is compared case-insensitively and without its parameters, and matches the registered MIME types it
+    /// is an alias of (e.g. `audio/x-m4a` for `audio/mp4`). Generic octet-stream values are matched by the extension
+    /// of the URL when `with_octet_stream` is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The `Content-Type` header value.
+    /// * `url` - The URL the header was sent for.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the registered MIME type that matched, or `None` if none did.
+    pub fn match_content_type(&self, content_type: &str, url: &str) -> Option<&str> {
+        let essence = parse_content_type(content_type)?;
+        if let Some((_, mime)) = self.iter().find(|(_, mime)| is_same_mime(mime, &essence)) {
+            return Some(mime);
+        }
+        if self.octet_stream && OCTET_STREAM_MIME_TYPES.contains(&essence.as_str()) {
+            return self.get_by_url(url);
+        }
+        None
+    }
+
+    /// Returns the registered MIME type of the extension of a URL.
+    pub fn get_by_url(&self, url: &str) -> Option<&str> {
+        let ext = url_extension(url)?;
+        self.iter().find(|(e, _)| *e == ext).map(|(_, mime)| mime)
+    }
+
+    /// Returns the number of registered extensions.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Checks whether the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mp4")]
+    #[test]
+    fn test_default() {
+        assert!(MimeRegistry::default().contains_mime("video/mp4"));
+        assert!(MimeRegistry::default()
+            .iter()
+            .all(|(_, mime)| MediaKind::of(mime) == MediaKind::Video));
+    }
+
+    #[cfg(all(feature = "mp3", feature = "jpg", feature = "pdf"))]
+    #[test]
+    fn test_for_kind() {
+        let audio = MimeRegistry::for_kind(MediaKind::Audio);
+
+        assert!(audio.contains_extension("mp3"));
+        assert!(!audio.contains_extension("mp4"));
+        assert!(MimeRegistry::for_kind(MediaKind::Image).contains_mime("image/jpeg"));
+        assert!(MimeRegistry::for_kind(MediaKind::Document).contains_mime("application/pdf"));
+    }
+
+    #[test]
+    fn test_parse_content_type() {
+        assert_eq!(
+            parse_content_type("Video/MP4; charset=binary"),
+            Some("video/mp4".to_string())
+        );
+        assert_eq!(
+            parse_content_type(" video/quicktime "),
+            Some("video/quicktime".to_string())
+        );
+        assert_eq!(parse_content_type("video"), None);
+        assert_eq!(parse_content_type("video/"), None);
+        assert_eq!(parse_content_type(""), None);
+    }
+
+    #[test]
+    fn test_match_content_type() {
+        let registry = MimeRegistry::from_iter([("mp4", "video/mp4")]);
+
+        assert_eq!(
+            registry.match_content_type("VIDEO/MP4; charset=binary", "http://example.com/a.mp4"),
+            Some("video/mp4")
+        );
+        assert_eq!(
+            registry.match_content_type("application/octet-stream", "http://example.com/a.mp4"),
+            None
+        );
+
+        let audio = MimeRegistry::from_iter([("m4a", "audio/mp4"), ("wav", "audio/x-wav")]);
+
+        assert_eq!(
+            audio.match_content_type("audio/x-m4a", "http://example.com/a.m4a"),
+            Some("audio/mp4")
+        );
+        assert_eq!(
+            audio.match_content_type("audio/wav", "http://example.com/a.wav"),
+            Some("audio/x-wav")
+        );
+        assert_eq!(
+            MimeRegistry::from_iter([("m4a", "audio/x-m4a")])
+                .match_content_type("audio/mp4", "http://example.com/a.m4a"),
+            Some("audio/x-m4a")
+        );
+
+        let registry = registry.with_octet_stream(true);
+
+        assert_eq!(
+            registry.match_content_type("application/octet-stream", "http://example.com/a.MP4?v=1"),
+            Some("video/mp4")
+        );
+        assert_eq!(
+            registry.match_content_type("binary/octet-stream", "http://example.com/a.mp4#t=10"),
+            Some("video/mp4")
+        );
+        assert_eq!(
+            registry.match_content_type("application/octet-stream", "http://example.com/a.zip"),
+            None
+        );
+        assert_eq!(
+            registry.match_content_type("application/octet-stream", "http://example.com/mp4"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x00\x00\x00\x20ftypisom")[0], "video/mp4");
+        assert!(sniff(b"\x00\x00\x00\x14ftypqt  ").contains(&"video/quicktime"));
+        assert_eq!(
+            sniff(b"\x00\x00\x00\x20ftypM4A \x00"),
+            &["audio/mp4", "audio/x-m4a"]
+        );
+        assert_eq!(sniff(b"RIFF\x00\x00\x00\x00AVI LIST")[0], "video/x-msvideo");
+        assert_eq!(sniff(b"FLV\x01\x05")[0], "video/x-flv");
+        assert!(sniff(b"\x1a\x45\xdf\xa3\x9f").contains(&"video/webm"));
+        assert_eq!(sniff(b"\x00\x00\x01\xba\x44"), &["video/mpeg"]);
+        assert_eq!(sniff(&[0x47; 600])[0], "video/mp2t");
+        assert_eq!(
+            sniff(b"\x30\x26\xb2\x75\x8e\x66\xcf\x11\xa6\xd9\x00\xaa\x00\x62\xce\x6c\x00")[0],
+            "video/x-ms-wmv"
+        );
+        assert_eq!(sniff(b"ID3\x04\x00"), &["audio/mpeg"]);
+        assert_eq!(sniff(b"%PDF-1.7"), &["application/pdf"]);
+        assert!(sniff(b"<!DOCTYPE html><html>").is_empty());
+        assert!(sniff(b"").is_empty());
+    }
+
+    #[cfg(feature = "mov")]
+    #[test]
+    fn test_get_by_url() {
+        let registry = MimeRegistry::default();
+
+        assert_eq!(
+            registry.get_by_url("http://example.com/a.MOV?v=1"),
+            Some("video/quicktime")
+        );
+        assert_eq!(registry.get_by_url("http://example.com/a.jpg"), None);
+        assert_eq!(registry.get_by_url("http://example.com/"), None);
+    }
+
+    #[test]
+    fn test_media_kind_of() {
+        assert_eq!(MediaKind::of("video/quicktime"), MediaKind::Video);
+        assert_eq!(MediaKind::of("audio/mpeg"), MediaKind::Audio);
+        assert_eq!(MediaKind::of("image/png"), MediaKind::Image);
+        assert_eq!(MediaKind::of("application/pdf"), MediaKind::Document);
+    }
+
+    #[test]
+    fn test_known() {
+        assert_eq!(MimeRegistry::known("MKV"), Some("video/x-matroska"));
+        assert_eq!(MimeRegistry::known("txt"), None);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut registry = MimeRegistry::empty();
+        registry.insert("MP4", "video/mp4");
+        registry.insert("mkv", "video/webm");
+        registry.insert("mkv", "video/x-matroska");
+
+        assert_eq!(
+            registry.iter().collect::<Vec<_>>(),
+            vec![("mp4", "video/mp4"), ("mkv", "video/x-matroska")]
+        );
+        assert!(registry.contains_extension("MKV"));
+        assert!(registry.contains_mime("video/x-matroska"));
+        assert!(!registry.contains_mime("video/webm"));
+
+        registry.remove("mp4");
+
+        assert_eq!(registry.extensions().collect::<Vec<_>>(), vec!["mkv"]);
+    }
+}
@@ -0,0 +1,107 @@
+/// Represents how the existence and MIME type of a URL were confirmed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FinderProbe {
+    /// `Content-Type` header of a HEAD request.
+    Head,
+    /// `Content-Type` header of a single byte range GET request, after the server rejected the HEAD request.
+    RangeGet,
+    /// Magic bytes of the leading bytes fetched with a ranged GET request.
+    Sniff,
+    /// Not confirmed, the scan being offline, but listed as is by a cached REST API response. The MIME type is the
+    /// one of the extension.
+    Offline,
+    /// Not confirmed, trusted by a `TrustVerifier`. The MIME type is the one of the extension.
+    Trusted,
+}
+
+impl FinderProbe {
+    /// Returns the name of the probe (e.g. `range_get`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinderProbe::Head => "head",
+            FinderProbe::RangeGet => "range_get",
+            FinderProbe::Sniff => "sniff",
+            FinderProbe::Offline => "offline",
+            FinderProbe::Trusted => "trusted",
+        }
+    }
+
+    /// Returns the probe of a name, the reverse of `as_str`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Head,
+            Self::RangeGet,
+            Self::Sniff,
+            Self::Offline,
+            Self::Trusted,
+        ]
+        .into_iter()
+        .find(|probe| probe.as_str() == name)
+    }
+}
+
+/// An existing URL found by `find_matches`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinderMatch {
+    /// The URL.
+    pub url: String,
+    /// The registered MIME type the URL matched.
+    pub mime_type: String,
+    /// How the URL was confirmed.
+    pub probe: FinderProbe,
+    /// The URL the redirects ended at, which is `url` when there were none.
+    pub final_url: String,
+    /// The URLs redirected to, in order, the last one being `final_url`.
+    pub redirects: Vec<String>,
+}
+
+impl FinderMatch {
+    /// Converts the match to a JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "url": self.url,
+            "mime_type": self.mime_type,
+            "probe": self.probe.as_str(),
+            "final_url": self.final_url,
+            "redirects": self.redirects,
+        })
+    }
+
+    /// Converts a JSON object to a match, the reverse of `to_json`.
+    pub fn from_json(json: &serde_json::Value) -> Option<Self> {
+        let string = |key: &str| json[key].as_str().map(str::to_string);
+        Some(Self {
+            url: string("url")?,
+            mime_type: string("mime_type")?,
+            probe: FinderProbe::from_name(json["probe"].as_str()?)?,
+            final_url: string("final_url")?,
+            redirects: json["redirects"]
+                .as_array()?
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+        })
+    }
+}
+
+/// What probing a URL found out about it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProbeOutcome {
+    /// The URL exists and is of a registered MIME type.
+    Found(FinderMatch),
+    /// The URL is missing (404 or 410 status) or of another type.
+    Absent,
+    /// The probe could not tell, because the request failed, the server answered another error status (e.g. 429
+    /// or 503), a redirect was not followed or the `ProbeCache` skipped the URL.
+    Unknown,
+}
+
+impl ProbeOutcome {
+    /// Returns the `FinderMatch` of a found URL.
+    pub fn found(self) -> Option<FinderMatch> {
+        match self {
+            Self::Found(found) => Some(found),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Name of the probe cache file in the cache directory.
+const PROBE_CACHE_FILE: &str = "probes.jsonl";
+
+/// Response of a probe request that proved a URL missing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeEntry {
+    /// The HTTP status.
+    pub status: u16,
+    /// The `Content-Type` header.
+    pub content_type: Option<String>,
+    /// The `Content-Length` header.
+    pub length: Option<u64>,
+    /// The `ETag` header.
+    pub etag: Option<String>,
+    /// When the URL was probed, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl ProbeEntry {
+    /// Creates an entry from the response of a probe request, timestamped now.
+    pub fn from_response(response: &reqwest::Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            status: response.status().as_u16(),
+            content_type: header(reqwest::header::CONTENT_TYPE),
+            length: header(reqwest::header::CONTENT_LENGTH).and_then(|v| v.parse().ok()),
+            etag: header(reqwest::header::ETAG),
+            timestamp: now(),
+        }
+    }
+
+    /// Checks whether the response is worth caching, i.e. proves the URL missing (404 or 410 status).
+    ///
+    /// Other responses are not cached, since they are either transient errors (e.g. 429 or 5xx status) or depend
+    /// on the MIME types and the verification of the scan (e.g. a 2xx status of another type, or a 403 status
+    /// rejecting a HEAD request without GET fallback).
+    pub fn is_cacheable(&self) -> bool {
+        matches!(self.status, 404 | 410)
+    }
+
+    /// Converts the entry of a URL to a JSON line.
+    fn to_json(&self, url: &str) -> serde_json::Value {
+        serde_json::json!({
+            "url": url,
+            "status": self.status,
+            "content_type": self.content_type,
+            "length": self.length,
+            "etag": self.etag,
+            "timestamp": self.timestamp,
+        })
+    }
+
+    /// Converts a JSON line to a URL and its entry.
+    fn from_json(json: &serde_json::Value) -> Option<(String, Self)> {
+        let string = |key: &str| json[key].as_str().map(str::to_string);
+        Some((
+            string("url")?,
+            Self {
+                status: u16::try_from(json["status"].as_u64()?).ok()?,
+                content_type: string("content_type"),
+                length: json["length"].as_u64(),
+                etag: string("etag"),
+                timestamp: json["timestamp"].as_u64()?,
+            },
+        ))
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// On-disk cache of the URLs that probe requests proved missing, so that later scans do not probe them again
+/// until their entry expires.
+///
+/// Clones share the same cache. Entries are stored as JSON lines in a file of the cache directory, which is
+/// compacted (expired and overwritten entries are dropped) when the cache is opened.
+#[derive(Clone, Debug)]
+pub struct ProbeCache {
+    entries: Arc<Mutex<HashMap<String, ProbeEntry>>>,
+    file: Arc<Mutex<LineWriter<File>>>,
+    ttl: Duration,
+}
+
+impl ProbeCache {
+    /// Opens the cache of a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The cache directory, which is created if it does not exist.
+    /// * `ttl` - How long entries are valid.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ProbeCache`, or an error if the cache file cannot be read or written.
+    pub fn open(dir: impl AsRef<Path>, ttl: Duration) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory: {e}"))?;
+        let path = dir.as_ref().join(PROBE_CACHE_FILE);
+
+        let mut entries = HashMap::new();
+        if let Ok(file) = File::open(&path) {
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|e| format!("Failed to read probe cache: {e}"))?;
+                // Lines truncated by an interrupted scan are ignored.
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) else {
+                    continue;
+                };
+                if let Some((url, entry)) = ProbeEntry::from_json(&json) {
+                    entries.insert(url, entry);
+                }
+            }
+        }
+        let expiry = now().saturating_sub(ttl.as_secs());
+        entries.retain(|_, entry| entry.timestamp > expiry);
+
+        let compacted = entries
+            .iter()
+            .map(|(url, entry)| entry.to_json(url).to_string() + "\n")
+            .collect::<String>();
+        fs::write(&path, compacted).map_err(|e| format!("Failed to write probe cache: {e}"))?;
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open probe cache: {e}"))?;
+
+        Ok(Self {
+            entries: Arc::new(Mutex::new(entries)),
+            file: Arc::new(Mutex::new(LineWriter::new(file))),
+            ttl,
+        })
+    }
+
+    /// Returns the unexpired entry of a URL.
+    pub fn get(&self, url: &str) -> Option<ProbeEntry> {
+        let expiry = now().saturating_sub(self.ttl.as_secs());
+        self.entries
+            .lock()
+            .unwrap()
+            .get(url)
+            .filter(|entry| entry.timestamp > expiry)
+            .cloned()
+    }
+
+    /// Stores the entry of a URL, which must prove it missing (cf. `ProbeEntry::is_cacheable`), since the cached
+    /// URLs are not probed again.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing, or an error if the entry is not cacheable or cannot be written to the cache
+    /// file.
+    pub fn insert(&self, url: &str, entry: ProbeEntry) -> Result<(), Box<dyn std::error::Error>> {
+        if !entry.is_cacheable() {
+            Err(format!(
+                "Failed to cache {url}: status {} does not prove it missing",
+                entry.status
+            ))?;
+        }
+        writeln!(self.file.lock().unwrap(), "{}", entry.to_json(url))
+            .map_err(|e| format!("Failed to write probe cache: {e}"))?;
+        self.entries.lock().unwrap().insert(url.to_string(), entry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    fn entry(status: u16, timestamp: u64) -> ProbeEntry {
+        ProbeEntry {
+            status,
+            content_type: Some("text/html".to_string()),
+            length: Some(42),
+            etag: None,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(entry(404, 0).is_cacheable());
+        assert!(entry(410, 0).is_cacheable());
+        assert!(!entry(200, 0).is_cacheable());
+        assert!(!entry(403, 0).is_cacheable());
+        assert!(!entry(429, 0).is_cacheable());
+        assert!(!entry(503, 0).is_cacheable());
+    }
+
+    #[test]
+    fn test_open_insert_and_get() {
+        let dir = TempDir::new("probe_cache");
+        let ttl = Duration::from_secs(3600);
+
+        let cache = ProbeCache::open(&dir, ttl).unwrap();
+        cache
+            .insert("http://example.com/a.mp4", entry(404, now()))
+            .unwrap();
+        cache
+            .insert("http://example.com/b.mp4", entry(404, now() - 7200))
+            .unwrap();
+        cache
+            .insert("http://example.com/a.mp4", entry(410, now()))
+            .unwrap();
+        assert!(cache
+            .insert("http://example.com/c.mp4", entry(503, now()))
+            .is_err());
+
+        assert_eq!(cache.get("http://example.com/a.mp4").unwrap().status, 410);
+        assert_eq!(cache.get("http://example.com/b.mp4"), None);
+        drop(cache);
+
+        let reopened = ProbeCache::open(&dir, ttl).unwrap();
+        assert_eq!(
+            reopened.get("http://example.com/a.mp4").unwrap().length,
+            Some(42)
+        );
+        assert_eq!(reopened.get("http://example.com/c.mp4"), None);
+        assert_eq!(
+            fs::read_to_string(dir.join(PROBE_CACHE_FILE))
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A cached response of the REST API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedResponse {
+    /// The `ETag` header, sent back as `If-None-Match`.
+    pub etag: Option<String>,
+    /// The `Last-Modified` header, sent back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+    /// The next link of the pagination (cf. `link_utils::get_next_link_from_headers`).
+    pub next_link: Option<String>,
+    /// The response body.
+    pub body: String,
+}
+
+/// On-disk cache of REST API responses keyed by URL, used to send conditional requests on later scans and to
+/// replay scans offline.
+///
+/// Each response is stored as a JSON file named after the 64-bit FNV-1a hash of its URL, which is stable across
+/// builds. Responses expire when their file is older than the TTL, and expired files are removed when the cache is
+/// opened.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// Returns the 64-bit FNV-1a hash of a URL.
+fn fnv1a(url: &str) -> u64 {
+    url.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl ResponseCache {
+    /// Opens the cache of a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The cache directory, which is created if it does not exist.
+    /// * `ttl` - How long responses are valid (e.g. `Duration::MAX` for recorded responses).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ResponseCache`, or an error if the directory cannot be created or read.
+    pub fn open(dir: impl AsRef<Path>, ttl: Duration) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory: {e}"))?;
+        let cache = Self {
+            dir: dir.as_ref().to_path_buf(),
+            ttl,
+        };
+
+        let entries =
+            fs::read_dir(&cache.dir).map_err(|e| format!("Failed to read response cache: {e}"))?;
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().is_some_and(|ext| ext == "json") && cache.is_expired(&path) {
+                // An expired file that cannot be removed is ignored by `get` anyway.
+                let _ = fs::remove_file(&path);
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Returns the path of the file of a URL.
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url)))
+    }
+
+    /// Checks whether a file was last written longer than the TTL ago.
+    fn is_expired(&self, path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > self.ttl)
+    }
+
+    /// Returns the unexpired cached response of a URL.
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let path = self.path(url);
+        if self.is_expired(&path) {
+            return None;
+        }
+        let json = serde_json::from_slice::<serde_json::Value>(&fs::read(path).ok()?).ok()?;
+        // Guards against hash collisions.
+        if json["url"] != url {
+            return None;
+        }
+        let string = |key: &str| json[key].as_str().map(str::to_string);
+        Some(CachedResponse {
+            etag: string("etag"),
+            last_modified: string("last_modified"),
+            next_link: string("next_link"),
+            body: string("body")?,
+        })
+    }
+
+    /// Stores the response of a URL.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing, or an error if the response cannot be written.
+    pub fn insert(
+        &self,
+        url: &str,
+        response: &CachedResponse,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::json!({
+            "url": url,
+            "etag": response.etag,
+            "last_modified": response.last_modified,
+            "next_link": response.next_link,
+            "body": response.body,
+        });
+        fs::write(self.path(url), json.to_string())
+            .map_err(|e| format!("Failed to write response cache: {e}").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let dir = TempDir::new("response_cache");
+
+        let cache = ResponseCache::open(&dir, Duration::from_secs(3600)).unwrap();
+        let response = CachedResponse {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            next_link: Some("http://example.com/wp-json/wp/v2/media?page=2".to_string()),
+            body: "[]".to_string(),
+        };
+        cache
+            .insert("http://example.com/wp-json/wp/v2/media", &response)
+            .unwrap();
+
+        assert_eq!(
+            cache.get("http://example.com/wp-json/wp/v2/media"),
+            Some(response)
+        );
+        assert_eq!(cache.get("http://example.com/wp-json/wp/v2/posts"), None);
+    }
+
+    #[test]
+    fn test_expiry() {
+        let dir = TempDir::new("response_expiry");
+        let url = "http://example.com/wp-json/wp/v2/media";
+
+        let cache = ResponseCache::open(&dir, Duration::from_secs(3600)).unwrap();
+        let response = CachedResponse {
+            etag: None,
+            last_modified: None,
+            next_link: None,
+            body: "[]".to_string(),
+        };
+        cache.insert(url, &response).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(cache.path(url))
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - Duration::from_secs(7200))
+            .unwrap();
+
+        assert_eq!(cache.get(url), None);
+        assert_eq!(
+            ResponseCache::open(&dir, Duration::MAX).unwrap().get(url),
+            Some(response)
+        );
+
+        ResponseCache::open(&dir, Duration::from_secs(3600)).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Set of the candidate URLs already verified, so that each URL is verified and emitted once.
+///
+/// Clones share the same set, which is how `find` calls remember URLs across targets (e.g. posts then media). URLs
+/// are remembered by their 64-bit hash to keep the memory footprint small on huge websites. The set can be backed
+/// by a file listing one URL per line, which is loaded on creation and appended to, so that a later scan skips
+/// the URLs of an earlier one. URLs are marked in memory before they are verified and only appended to the file
+/// once they are confirmed or rejected (cf. `mark` and `persist`), so that an interrupted or failed verification
+/// is retried by the next scan.
+#[derive(Clone, Debug, Default)]
+pub struct SeenUrls {
+    hashes: Arc<Mutex<HashSet<u64>>>,
+    file: Option<Arc<Mutex<LineWriter<File>>>>,
+}
+
+/// Returns the 64-bit hash of a URL.
+fn hash(url: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl SeenUrls {
+    /// Creates a set backed by a file, loading the URLs it already lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, which is created if it does not exist.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SeenUrls`, or an error if the file cannot be read or created.
+    pub fn with_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open seen URLs file: {e}"))?;
+
+        let mut hashes = HashSet::new();
+        for line in BufReader::new(&file).lines() {
+            let line = line.map_err(|e| format!("Failed to read seen URLs file: {e}"))?;
+            if !line.is_empty() {
+                hashes.insert(hash(&line));
+            }
+        }
+
+        Ok(Self {
+            hashes: Arc::new(Mutex::new(hashes)),
+            file: Some(Arc::new(Mutex::new(LineWriter::new(file)))),
+        })
+    }
+
+    /// Remembers a URL, in memory and in the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the URL was not seen before, `false` otherwise, or an error if the URL
+    /// cannot be written to the file.
+    pub fn insert(&self, url: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.mark(url) {
+            return Ok(false);
+        }
+        self.persist(url)?;
+        Ok(true)
+    }
+
+    /// Remembers a URL in memory only, e.g. before verifying it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the URL was not seen before, `false` otherwise.
+    pub fn mark(&self, url: &str) -> bool {
+        self.hashes.lock().unwrap().insert(hash(url))
+    }
+
+    /// Appends a URL to the file, if any, e.g. once it is verified.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` that is an error if the URL cannot be written to the file.
+    pub fn persist(&self, url: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(file) = &self.file {
+            writeln!(file.lock().unwrap(), "{url}")
+                .map_err(|e| format!("Failed to write seen URLs file: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether a URL was seen before.
+    pub fn contains(&self, url: &str) -> bool {
+        self.hashes.lock().unwrap().contains(&hash(url))
+    }
+
+    /// Returns the number of URLs seen.
+    pub fn len(&self) -> usize {
+        self.hashes.lock().unwrap().len()
+    }
+
+    /// Checks whether no URL was seen.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn test_insert() {
+        let seen = SeenUrls::default();
+        let shared = seen.clone();
+
+        assert!(seen.insert("http://example.com/a.mp4").unwrap());
+        assert!(!shared.insert("http://example.com/a.mp4").unwrap());
+        assert!(shared.contains("http://example.com/a.mp4"));
+        assert!(!seen.contains("http://example.com/b.mp4"));
+        assert_eq!(seen.len(), 1);
+    }
+
+    #[test]
+    fn test_with_file() {
+        let dir = TempDir::new("seen");
+        let path = dir.join("seen.txt");
+
+        let seen = SeenUrls::with_file(&path).unwrap();
+        assert!(seen.is_empty());
+        assert!(seen.insert("http://example.com/a.mp4").unwrap());
+        assert!(seen.insert("http://example.com/b.mp4").unwrap());
+        drop(seen);
+
+        let resumed = SeenUrls::with_file(&path).unwrap();
+        assert_eq!(resumed.len(), 2);
+        assert!(!resumed.insert("http://example.com/a.mp4").unwrap());
+        assert!(resumed.insert("http://example.com/c.mp4").unwrap());
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "http://example.com/a.mp4\nhttp://example.com/b.mp4\nhttp://example.com/c.mp4\n"
+        );
+    }
+
+    #[test]
+    fn test_mark_and_persist() {
+        let dir = TempDir::new("seen_mark");
+        let path = dir.join("seen.txt");
+
+        let seen = SeenUrls::with_file(&path).unwrap();
+        assert!(seen.mark("http://example.com/a.mp4"));
+        assert!(seen.mark("http://example.com/b.mp4"));
+        assert!(!seen.mark("http://example.com/a.mp4"));
+        seen.persist("http://example.com/b.mp4").unwrap();
+        drop(seen);
+
+        let resumed = SeenUrls::with_file(&path).unwrap();
+        assert!(!resumed.contains("http://example.com/a.mp4"));
+        assert!(resumed.contains("http://example.com/b.mp4"));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{date_utils, link_utils, FinderConfig};
+
+use async_stream::try_stream;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+
+/// Well-known sitemap locations of WordPress core (5.5+), Yoast SEO and Rank Math.
+const SITEMAP_PATHS: &[&str] = &[
+    "/wp-sitemap.xml",
+    "/sitemap_index.xml",
+    "/sitemap.xml",
+    "/video-sitemap.xml",
+];
+
+/// Error of `get_items_stream` when the website has no sitemap.
+#[derive(Debug)]
+pub struct NoSitemap;
+
+impl std::fmt::Display for NoSitemap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "No sitemap found")
+    }
+}
+
+impl std::error::Error for NoSitemap {}
+
+lazy_static! {
+    /// Regular expression to match and capture the content of `<sitemap>` entries of a sitemap index.
+    static ref SITEMAP_RE: Regex = Regex::new(r"(?s)<sitemap>(.*?)</sitemap>").unwrap();
+
+    /// Regular expression to match and capture the content of `<url>` entries of a sitemap.
+    static ref URL_RE: Regex = Regex::new(r"(?s)<url>(.*?)</url>").unwrap();
+
+    /// Regular expression to match and capture the `<loc>` of an entry.
+    static ref LOC_RE: Regex = Regex::new(r"(?s)<loc>(.*?)</loc>").unwrap();
+
+    /// Regular expression to match and capture the `<lastmod>` of an entry.
+    static ref LASTMOD_RE: Regex = Regex::new(r"(?s)<lastmod>(.*?)</lastmod>").unwrap();
+
+    /// Regular expression to match and capture the `<image:loc>` elements of an entry.
+    static ref IMAGE_RE: Regex = Regex::new(r"(?s)<image:loc>(.*?)</image:loc>").unwrap();
+
+    /// Regular expression to match and capture the `<video:content_loc>` elements of an entry.
+    static ref VIDEO_RE: Regex = Regex::new(r"(?s)<video:content_loc>(.*?)</video:content_loc>").unwrap();
+
+    /// Regular expression to match sitemaps listing taxonomy archives or authors rather than posts.
+    static ref ARCHIVE_SITEMAP_RE: Regex =
+        Regex::new(r"-(?:taxonomies|users)-|/(?:category|post_tag|post_format|author)-sitemap").unwrap();
+}
+
+/// Decodes the text of an XML element (CDATA sections and predefined entities).
+pub fn decode(text: &str) -> String {
+    let text = text.trim();
+    text.strip_prefix("<![CDATA[")
+        .and_then(|text| text.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            text.replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+}
+
+/// Captures and decodes every match of `re` in `text`.
+fn capture_all<'a>(re: &'a Regex, text: &'a str) -> impl Iterator<Item = String> + 'a {
+    re.captures_iter(text).map(|captures| decode(&captures[1]))
+}
+
+/// Parses a sitemap index and returns the URLs of the post sitemaps it lists, skipping taxonomy and author
+/// sitemaps as well as sitemaps last modified outside of the `FinderConfig` date bounds.
+fn parse_index(config: &FinderConfig, xml: &str) -> Vec<String> {
+    capture_all(&SITEMAP_RE, xml)
+        .filter_map(|entry| {
+            let loc = LOC_RE.captures(&entry).map(|c| decode(&c[1]))?;
+            let lastmod = LASTMOD_RE
+                .captures(&entry)
+                .and_then(|c| date_utils::normalize(&c[1]));
+            // The lastmod of a sitemap is the most recent lastmod of its entries.
+            let is_recent = lastmod.is_none_or(|lastmod| date_utils::is_after(config, &lastmod));
+            (is_recent && !ARCHIVE_SITEMAP_RE.is_match(&loc)).then_some(loc)
+        })
+        .collect()
+}
+
+/// Parses a sitemap and converts its entries to items shaped like WordPress REST API items.
+///
+/// Each entry becomes a post item (`link`, and its `lastmod` as `date` and `modified`), while its images and
+/// videos become media items (`source_url`). Entries whose `lastmod` is out of the `modified_after` and
+/// `modified_before` bounds of the `FinderConfig` are skipped, as well as entries last modified before the `after`
+/// bound, which were published before it too.
+fn parse_urlset(config: &FinderConfig, xml: &str) -> Vec<serde_json::Value> {
+    capture_all(&URL_RE, xml)
+        .filter_map(|entry| {
+            let lastmod = LASTMOD_RE
+                .captures(&entry)
+                .and_then(|c| date_utils::normalize(&c[1]));
+            // The lastmod of an entry is its modification date, its publication date is only known to be earlier.
+            if lastmod.as_deref().is_some_and(|lastmod| {
+                !date_utils::is_in_range(config, None, Some(lastmod))
+                    || !date_utils::is_after(config, lastmod)
+            }) {
+                return None;
+            }
+            let post = LOC_RE.captures(&entry).map(|c| {
+                serde_json::json!({"link": decode(&c[1]), "date": lastmod, "modified": lastmod})
+            });
+            let media = capture_all(&IMAGE_RE, &entry)
+                .chain(capture_all(&VIDEO_RE, &entry))
+                .map(|url| serde_json::json!({ "source_url": url }))
+                .collect::<Vec<_>>();
+            Some(post.into_iter().chain(media))
+        })
+        .flatten()
+        .collect()
+}
+
+/// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It walks
+/// the sitemap indexes found at well-known locations and yields, for each sitemap, its entries as items shaped
+/// like WordPress REST API items.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// An asynchronous `futures_core::Stream` of `Result` containing the items of a sitemap if successful, or an
+/// error.
+pub fn get_items_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+) -> impl Stream<Item = Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>> + 'a {
+    try_stream! {
+        if config.url.is_empty() {
+            Err("URL is required")?;
+        }
+
+        let mut queue = SITEMAP_PATHS
+            .iter()
+            .map(|path| format!("{}{path}", config.url))
+            .collect::<VecDeque<_>>();
+        let mut visited = HashSet::new();
+        let mut found = false;
+
+        while let Some(url) = queue.pop_front() {
+            if !visited.insert(url.clone()) {
+                continue;
+            }
+
+            let response = link_utils::send_with_retry(client.get(&url), &config.retry)
+                .await
+                .map_err(|e| format!("Failed to send request: {e}"))?;
+
+            // Root sitemaps are guessed, so missing ones are expected.
+            if !response.status().is_success() {
+                continue;
+            }
+
+            // Plugins redirect core sitemaps to their own (e.g. `/wp-sitemap.xml` to `/sitemap_index.xml`).
+            let final_url = response.url().to_string();
+            if final_url != url && !visited.insert(final_url) {
+                continue;
+            }
+
+            found = true;
+
+            let xml = response.text().await.map_err(|e| {
+                format!("Failed to read response body: {e}")
+            })?;
+
+            queue.extend(parse_index(config, &xml));
+
+            let items = parse_urlset(config, &xml);
+            if !items.is_empty() {
+                yield items;
+            }
+        }
+
+        if !found {
+            Err(NoSitemap)?;
+        }
+    }
+}
+
+/// This function takes an HTTP client and a configuration object, and returns a stream of JSON values. It yields,
+/// for each sitemap, its entries as a JSON array of items shaped like WordPress REST API items, so that they can
+/// go through the same extraction as `api::get_stream` pages.
+///
+/// # Arguments
+///
+/// * `client` - `reqwest::Client` used to make HTTP requests.
+/// * `config` - The `FinderConfig`.
+///
+/// # Returns
+///
+/// An asynchronous `futures_core::Stream` of `Result` containing a JSON array `String` if successful, or an
+/// error.
+pub fn get_stream<'a>(
+    client: &'a reqwest::Client,
+    config: &'a FinderConfig,
+) -> impl Stream<Item = Result<String, Box<dyn std::error::Error>>> + 'a {
+    get_items_stream(client, config)
+        .map(|items| items.map(|items| serde_json::Value::Array(items).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::pin_mut;
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_parse_index() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>http://example.com/wp-sitemap-posts-post-1.xml</loc></sitemap>
+                <sitemap><loc>http://example.com/wp-sitemap-taxonomies-category-1.xml</loc></sitemap>
+                <sitemap><loc>http://example.com/wp-sitemap-users-1.xml</loc></sitemap>
+                <sitemap><loc>http://example.com/post-sitemap.xml</loc><lastmod>2020-01-01T00:00:00+00:00</lastmod></sitemap>
+                <sitemap><loc>http://example.com/page-sitemap.xml?a=1&amp;b=2</loc><lastmod>2022-01-01T00:00:00+00:00</lastmod></sitemap>
+            </sitemapindex>"#;
+
+        let config = FinderConfig {
+            after: Some("2021-01-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_index(&config, xml),
+            vec![
+                "http://example.com/wp-sitemap-posts-post-1.xml",
+                "http://example.com/page-sitemap.xml?a=1&b=2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_urlset() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <url>
+                    <loc>http://example.com/first-post/</loc>
+                    <lastmod>2021-01-05T10:00:00+00:00</lastmod>
+                    <image:image><image:loc><![CDATA[http://example.com/wp-content/uploads/2021/01/poster.jpg]]></image:loc></image:image>
+                    <video:video><video:content_loc>http://example.com/wp-content/uploads/2021/01/clip.mp4</video:content_loc></video:video>
+                </url>
+                <url><loc>http://example.com/old-post/</loc><lastmod>2019-01-05</lastmod></url>
+                <url><loc>http://example.com/undated-post/</loc></url>
+            </urlset>"#;
+
+        let config = FinderConfig {
+            after: Some("2020-01-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_urlset(&config, xml),
+            vec![
+                serde_json::json!({"link": "http://example.com/first-post/", "date": "2021-01-05T10:00:00", "modified": "2021-01-05T10:00:00"}),
+                serde_json::json!({"source_url": "http://example.com/wp-content/uploads/2021/01/poster.jpg"}),
+                serde_json::json!({"source_url": "http://example.com/wp-content/uploads/2021/01/clip.mp4"}),
+                serde_json::json!({"link": "http://example.com/undated-post/", "date": null, "modified": null}),
+            ]
+        );
+
+        // A post modified after the before bound may have been published before it.
+        let config = FinderConfig {
+            before: Some("2020-01-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(parse_urlset(&config, xml).len(), 5);
+        let config = FinderConfig {
+            modified_before: Some("2020-01-01T00:00:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_urlset(&config, xml),
+            vec![
+                serde_json::json!({"link": "http://example.com/old-post/", "date": "2019-01-05T00:00:00", "modified": "2019-01-05T00:00:00"}),
+                serde_json::json!({"link": "http://example.com/undated-post/", "date": null, "modified": null}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_stream() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let index_mock = server
+            .mock("GET", "/wp-sitemap.xml")
+            .with_body(format!(
+                "<sitemapindex><sitemap><loc>{url}/wp-sitemap-posts-post-1.xml</loc></sitemap></sitemapindex>"
+            ))
+            .create_async()
+            .await;
+
+        let posts_mock = server
+            .mock("GET", "/wp-sitemap-posts-post-1.xml")
+            .with_body(format!(
+                "<urlset><url><loc>{url}/hello-world/</loc></url></urlset>"
+            ))
+            .create_async()
+            .await;
+
+        let not_found_mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(
+                    r"^/(sitemap_index|sitemap|video-sitemap)\.xml$".to_string(),
+                ),
+            )
+            .with_status(404)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: url.clone(),
+            ..Default::default()
+        };
+
+        let stream = get_stream(&client, &config);
+        pin_mut!(stream);
+
+        let bodies = stream
+            .filter_map(|res| async { res.ok() })
+            .collect::<Vec<_>>()
+            .await;
+
+        index_mock.assert_async().await;
+        posts_mock.assert_async().await;
+        not_found_mock.assert_async().await;
+
+        assert_eq!(
+            bodies,
+            vec![serde_json::json!([{"link": format!("{url}/hello-world/"), "date": null, "modified": null}]).to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_with_no_sitemap() {
+        let mut server = mockito::Server::new_async().await;
+
+        let not_found_mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(404)
+            .expect(SITEMAP_PATHS.len())
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let config = FinderConfig {
+            url: server.url(),
+            ..Default::default()
+        };
+
+        let stream = get_stream(&client, &config);
+        pin_mut!(stream);
+
+        let next = stream.next().await.unwrap();
+
+        not_found_mock.assert_async().await;
+
+        assert!(next.err().unwrap().is::<NoSitemap>());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{Candidate, ExtractContext, Extractor};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extracts the URL of an ACF field, like the site-specific extractors of embedders.
+pub struct AcfExtractor;
+
+impl Extractor for AcfExtractor {
+    fn name(&self) -> &str {
+        "acf"
+    }
+
+    fn extract(&self, item: &serde_json::Value, _: &ExtractContext) -> Vec<Candidate> {
+        item.pointer("/acf/video_file")
+            .and_then(|v| v.as_str())
+            .map(|url| Candidate::new(url, 1.0, "acf.video_file"))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Temporary directory of a test, removed when dropped, even if the test fails.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory named after a test and the process, so that concurrent test runs do not share it.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("pf_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Returns the path of the directory.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Returns the path of a file of the directory.
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        self.path()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,139 +1,238 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
-use crate::mime_types::SUPPORTED_MIME_TYPES;
+use crate::extractor::{media_objects, Candidate, ExtractContext, Extractors};
+use crate::mime_types::MimeRegistry;
+use crate::FinderBudget;
 
 lazy_static! {
-    /// Regular expression to match URLs of media files in the content body.
-    static ref BODY_RE: Regex = {
-        Regex::new(&format!(
-            r"https?://[^/]+(?:/blog)?/wp-content/uploads/\d{{4}}/\d{{2}}/[^/]+\.(?:{})",
-            SUPPORTED_MIME_TYPES
-            .iter()
-            .map(|(ext, _)| *ext)
-            .collect::<Vec<_>>()
-            .join("|")
-        )).unwrap()
-    };
+    /// Regular expression to match and capture the upload base of a media URL and whether it uses dated folders.
+    static ref UPLOAD_RE: Regex =
+        Regex::new(r"^(https?://.+?/wp-content/uploads(?:/sites/\d+)?)/(\d{4}/\d{2}/)?[^/]+$").unwrap();
+
+    /// Regular expression to match a relative upload path using year/month folders.
+    static ref DATED_FILE_RE: Regex = Regex::new(r"^\d{4}/\d{2}/[^/]+$").unwrap();
+
+    /// Regular expression to match and capture the origin URL wrapped by Jetpack's Photon CDN.
+    static ref PHOTON_RE: Regex = Regex::new(r"^(https?://)i\d\.wp\.com/([^?#]+)").unwrap();
+}
+
+/// An uploads folder observed on a website (e.g. `https://cdn.example.com/wp-content/uploads/sites/2`).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UploadBase {
+    /// The URL of the folder, without trailing slash.
+    pub url: String,
+    /// Whether files are organized in year/month subfolders.
+    pub dated: bool,
+}
 
-    /// Regular expression to match and capture base URL and slug of a complex URL.
-    static ref LINK_RE: Regex = Regex::new(r"^(https?://[^/]+)(?:/[^/]+)*/([^/]+)/?$").unwrap();
+impl UploadBase {
+    /// Learns the upload base from a media `source_url` and, when available, its `media_details.file`
+    /// relative path, which is the only reliable hint for custom `UPLOADS` folders and offloaded media.
+    pub(crate) fn learn(source_url: &str, file: Option<&str>) -> Option<Self> {
+        let source_url = unwrap_photon(source_url);
+        if let Some(file) = file {
+            if let Some(url) = source_url
+                .strip_suffix(file)
+                .and_then(|url| url.strip_suffix('/'))
+            {
+                return Some(Self {
+                    url: url.to_string(),
+                    dated: DATED_FILE_RE.is_match(file),
+                });
+            }
+        }
+        let captures = UPLOAD_RE.captures(&source_url)?;
+        Some(Self {
+            url: captures.get(1)?.as_str().to_string(),
+            dated: captures.get(2).is_some(),
+        })
+    }
 
-    /// Regular expression to match and capture month and year of a date string.
-    static ref DATE_RE: Regex = Regex::new(r"^(\d{4})-(\d{2})-\d{2}t\d{2}:\d{2}:\d{2}$").unwrap();
+    /// Builds the URL of a file named `name` uploaded at the given year and month.
+    pub(crate) fn file_url(&self, year: &str, month: &str, name: &str) -> String {
+        if self.dated {
+            format!("{}/{year}/{month}/{name}", self.url)
+        } else {
+            format!("{}/{name}", self.url)
+        }
+    }
 }
 
+/// Set of the upload folders learned on a website, shared by the `find` calls of a configuration.
+///
+/// Clones share the same set, so that folders learned from the `source_url` of media are used to extract and guess
+/// the URLs of posts scanned later, and conversely.
+#[derive(Clone, Debug, Default)]
+pub struct UploadBases(Arc<Mutex<HashSet<UploadBase>>>);
+
+impl UploadBases {
+    /// Returns a copy of the upload folders learned so far.
+    pub fn snapshot(&self) -> HashSet<UploadBase> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Remembers upload folders.
+    pub fn extend(&self, upload_bases: &HashSet<UploadBase>) {
+        self.0.lock().unwrap().extend(upload_bases.iter().cloned());
+    }
+
+    /// Returns the number of upload folders learned.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// Checks whether no upload folder was learned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Rewrites Jetpack Photon URLs (`https://i0.wp.com/example.com/...`) to their origin URL.
+pub(crate) fn unwrap_photon(url: &str) -> String {
+    match PHOTON_RE.captures(url) {
+        Some(captures) => format!("{}{}", &captures[1], &captures[2]),
+        None => url.to_string(),
+    }
+}
+
+/// Merges the candidates of the same URL, keeping their highest confidence, and sorts them by decreasing
+/// confidence.
+fn merge(candidates: impl IntoIterator<Item = Candidate>) -> Vec<Candidate> {
+    let mut merged = HashMap::<String, Candidate>::new();
+    for candidate in candidates {
+        match merged.get(&candidate.url) {
+            Some(known) if known.confidence >= candidate.confidence => {}
+            _ => {
+                merged.insert(candidate.url.clone(), candidate);
+            }
+        }
+    }
+    let mut merged = merged.into_values().collect::<Vec<_>>();
+    merged.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.url.cmp(&b.url))
+    });
+    merged
+}
+
+#[derive(Default)]
 pub struct Xtract {
     json: Vec<serde_json::Value>,
+    upload_bases: HashSet<UploadBase>,
+    mime_types: MimeRegistry,
+    extractors: Extractors,
+    budget: FinderBudget,
 }
 
 impl Xtract {
     pub fn new(json: &str) -> Self {
+        let json = serde_json::from_str::<Vec<serde_json::Value>>(json).unwrap();
+        let upload_bases = json
+            .iter()
+            .flat_map(|value| media_objects(value).map(|(media, _)| media))
+            .filter_map(|media| {
+                UploadBase::learn(
+                    media["source_url"].as_str()?,
+                    media
+                        .pointer("/media_details/file")
+                        .and_then(|v| v.as_str()),
+                )
+            })
+            .collect();
         Self {
-            json: serde_json::from_str::<Vec<serde_json::Value>>(&json.to_lowercase()).unwrap(),
+            json,
+            upload_bases,
+            ..Default::default()
         }
     }
 
-    /// Extracts URLs from the main `source_url` field of media and posts.
-    ///
-    /// This function extracts URLs from the main `source_url` field of media and posts, constructs URLs for media
-    /// files based on the extension of the URL.
-    ///
-    /// # Returns
-    ///
-    /// A `HashSet` containing the extracted URLs.
-    fn p1(&self) -> HashSet<String> {
+    /// Returns the upload folders observed so far.
+    pub fn upload_bases(&self) -> &HashSet<UploadBase> {
+        &self.upload_bases
+    }
+
+    /// Adds upload folders observed elsewhere on the website (e.g. on previous pages).
+    pub fn with_upload_bases(mut self, upload_bases: &HashSet<UploadBase>) -> Self {
+        self.upload_bases.extend(upload_bases.iter().cloned());
+        self
+    }
+
+    /// Sets the MIME types to look for, which default to the types of the enabled features.
+    pub fn with_mime_types(mut self, mime_types: &MimeRegistry) -> Self {
+        self.mime_types = mime_types.clone();
+        self
+    }
+
+    /// Sets the extractors, which default to the built-in heuristics.
+    pub fn with_extractors(mut self, extractors: &Extractors) -> Self {
+        self.extractors = extractors.clone();
+        self
+    }
+
+    /// Sets which candidates are kept, which defaults to all of them.
+    pub fn with_budget(mut self, budget: &FinderBudget) -> Self {
+        self.budget = *budget;
+        self
+    }
+
+    /// Extracts URLs from every item with an extractor.
+    #[cfg(test)]
+    fn extract(&self, extractor: &dyn crate::Extractor) -> HashSet<String> {
+        let context = ExtractContext::new(&self.mime_types, &self.upload_bases);
         self.json
             .iter()
-            .flat_map(|value| {
-                let mut urls = HashSet::new();
-                if let Some(url) = value["source_url"].as_str() {
-                    urls.insert(url);
-                }
-                if let Some(array) = value["_embedded"]["wp:featuredmedia"].as_array() {
-                    for media in array {
-                        if let Some(url) = media["source_url"].as_str() {
-                            urls.insert(url);
-                        }
-                    }
-                }
-                urls
-            })
-            .filter_map(|url| {
-                let (base_url, extension) = url.rsplit_once('.')?;
-                if SUPPORTED_MIME_TYPES
-                    .iter()
-                    .any(|(ext, _)| *ext == extension)
-                {
-                    Some(vec![format!("{}.mp4", base_url)].into_iter().collect())
-                } else {
-                    Some(
-                        SUPPORTED_MIME_TYPES
-                            .iter()
-                            .map(|(ext, _)| format!("{}.{}", base_url, *ext))
-                            .collect::<HashSet<_>>(),
-                    )
-                }
-            })
-            .flatten()
+            .flat_map(|item| extractor.extract(item, &context))
+            .map(|candidate| candidate.url)
             .collect()
     }
 
-    /// Extracts URLs from the content and excerpt fields of posts.
+    /// Extracts candidate URLs from JSON string with every extractor.
     ///
-    /// This function searches for URLs in the `content.rendered` and `excerpt.rendered` fields of posts,
-    /// matches them against the `BODY_RE` regex, and collects them.
+    /// Candidates below the minimum confidence of the budget are dropped, as well as the least likely candidates of
+    /// an item beyond its maximum number of guesses.
     ///
     /// # Returns
     ///
-    /// A `HashSet` containing the extracted URLs.
-    fn p2(&self) -> HashSet<String> {
-        self.json
-            .iter()
-            .flat_map(|item| {
-                item.pointer("/content/rendered")
-                    .and_then(|v| v.as_str())
-                    .into_iter()
-                    .chain(item.pointer("/excerpt/rendered").and_then(|v| v.as_str()))
-            })
-            .flat_map(|text| {
-                BODY_RE
-                    .find_iter(&text.replace('\\', "")) // Remove backslashes if necessary
-                    .map(|m| m.as_str().to_string())
-                    .collect::<Vec<_>>()
-            })
-            .collect()
+    /// A `Vec` containing the `Candidate`s, each URL once with its highest confidence, sorted by decreasing
+    /// confidence.
+    pub fn candidates(&self) -> Vec<Candidate> {
+        let context = ExtractContext::new(&self.mime_types, &self.upload_bases);
+        merge(self.json.iter().flat_map(|item| {
+            let mut candidates = merge(
+                self.extractors
+                    .iter()
+                    .flat_map(|extractor| extractor.extract(item, &context))
+                    .filter(|candidate| candidate.confidence >= self.budget.min_confidence),
+            );
+            if let Some(max_guesses) = self.budget.max_guesses_per_item {
+                candidates.truncate(max_guesses);
+            }
+            candidates
+        }))
     }
 
-    /// Constructs URLs based on the `link` and `date` fields of posts.
-    ///
-    /// This function constructs URLs for media files based on the `link` and `date` fields of posts,
-    /// using the `LINK_RE` and `DATE_RE` regexes to extract parts of the URL and date.
+    /// Extracts candidate URLs from JSON string with every extractor, without merging them nor applying the budget.
     ///
     /// # Returns
     ///
-    /// A `HashSet` containing the constructed URLs.
-    fn p3(&self) -> HashSet<String> {
+    /// A `Vec` containing each item, the name of the extractor and the `Candidate` it extracted from the item, in
+    /// the order of the items and extractors.
+    pub fn listing(&self) -> Vec<(&serde_json::Value, &str, Candidate)> {
+        let context = &ExtractContext::new(&self.mime_types, &self.upload_bases);
         self.json
             .iter()
-            .filter_map(|item| {
-                let link = LINK_RE.captures(item["link"].as_str()?)?;
-                let date = DATE_RE.captures(item["date"].as_str()?)?;
-
-                let base_url = link.get(1)?.as_str();
-                let slug = link.get(2)?.as_str();
-                let year = date.get(1)?.as_str();
-                let month = date.get(2)?.as_str();
-
-                Some(SUPPORTED_MIME_TYPES.iter().flat_map(move |(ext, _)| {
-                    [
-                        format!("{base_url}/wp-content/uploads/{year}/{month}/{slug}.{ext}"),
-                        format!("{base_url}/blog/wp-content/uploads/{year}/{month}/{slug}.{ext}"),
-                    ]
-                }))
+            .flat_map(|item| {
+                self.extractors.iter().flat_map(move |extractor| {
+                    extractor
+                        .extract(item, context)
+                        .into_iter()
+                        .map(move |candidate| (item, extractor.name(), candidate))
+                })
             })
-            .flatten()
             .collect()
     }
 
@@ -142,11 +241,11 @@ impl Xtract {
     /// # Returns
     ///
     /// A `HashSet` containing the extracted URLs.
+    #[cfg(test)]
     pub fn run(&self) -> HashSet<String> {
-        self.p1()
+        self.candidates()
             .into_iter()
-            .chain(self.p2())
-            .chain(self.p3())
+            .map(|candidate| candidate.url)
             .collect()
     }
 }
@@ -157,6 +256,10 @@ mod tests {
 
     use super::*;
 
+    #[cfg(any(feature = "mp4", feature = "mov"))]
+    use crate::extractor::ContentExtractor;
+    use crate::extractor::{SlugExtractor, SourceUrlExtractor};
+
     #[test]
     fn test_new() {
         Xtract::new("[]");
@@ -174,22 +277,25 @@ mod tests {
         Xtract::new("{}");
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p1_with_media_target() {
         assert_eq!(Xtract {
             json: vec![
                 json!({"source_url": "http://example.com/wp-content/uploads/2021/01/video.mp4"}),
-            ]
+            ],
+            ..Default::default()
         }
-        .p1().iter().next().unwrap(),
+        .extract(&SourceUrlExtractor).iter().next().unwrap(),
         "http://example.com/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p1_with_posts_target() {
         assert_eq!(Xtract { json: vec![
             json!({"_embedded": {"wp:featuredmedia": [{"source_url": "http://example.com/wp-content/uploads/2021/01/video.mp4"}]}}),
-        ] }.p1().iter().next().unwrap(), "http://example.com/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&SourceUrlExtractor).iter().next().unwrap(), "http://example.com/wp-content/uploads/2021/01/video.mp4")
     }
 
     #[test]
@@ -197,72 +303,89 @@ mod tests {
         assert_eq!(Xtract {
             json: vec![
                 json!({"source_url": "http://example.com/wp-content/uploads/2021/01/image.jpg"}),
-            ]
+            ],
+            ..Default::default()
         }
-        .p1().len(), SUPPORTED_MIME_TYPES.len())
+        .extract(&SourceUrlExtractor).len(), MimeRegistry::default().len())
+    }
+
+    #[cfg(feature = "mov")]
+    #[test]
+    fn test_p1_with_media_and_mov() {
+        assert_eq!(Xtract { json: vec![
+            json!({"source_url": "http://example.com/wp-content/uploads/2021/01/video.mov"}),
+        ], ..Default::default() }.extract(&SourceUrlExtractor).iter().next().unwrap(), "http://example.com/wp-content/uploads/2021/01/video.mov")
     }
 
     #[test]
     fn test_p1_with_posts_and_jpg() {
         assert_eq!(Xtract { json: vec![
             json!({"_embedded": {"wp:featuredmedia": [{"source_url": "http://www.example.com/wp-content/uploads/2021/01/image.jpg"}]}}),
-        ] }.p1().len(), SUPPORTED_MIME_TYPES.len())
+        ], ..Default::default() }.extract(&SourceUrlExtractor).len(), MimeRegistry::default().len())
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p1_with_media_and_blog_prefix() {
         assert_eq!(Xtract { json: vec![
             json!({"source_url": "http://example.com/blog/wp-content/uploads/2021/01/video.mp4"}),
-        ] }.p1().iter().next().unwrap(), "http://example.com/blog/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&SourceUrlExtractor).iter().next().unwrap(), "http://example.com/blog/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p1_with_posts_and_blog_prefix() {
         assert_eq!(Xtract { json: vec![
             json!({"_embedded": {"wp:featuredmedia": [{"source_url": "http://example.com/blog/wp-content/uploads/2021/01/video.mp4"}]}}),
-        ] }.p1().iter().next().unwrap(), "http://example.com/blog/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&SourceUrlExtractor).iter().next().unwrap(), "http://example.com/blog/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p2_with_content_field() {
         assert_eq!(Xtract { json: vec![
             json!({"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/video.mp4"}, "excerpt": {"rendered": ""}}),
-        ] }.p2().iter().next().unwrap(), "http://example.com/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "http://example.com/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p2_with_excerpt_field() {
         assert_eq!(Xtract { json: vec![
             json!({"content": {"rendered": ""}, "excerpt": {"rendered": "https://example.com/wp-content/uploads/2021/01/video.mp4"}}),
-        ] }.p2().iter().next().unwrap(), "https://example.com/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://example.com/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p2_with_blog_prefix() {
         assert_eq!(Xtract { json: vec![
             json!({"content": {"rendered": "https://www.example.com/blog/wp-content/uploads/2021/01/video.mp4"}, "excerpt": {"rendered": ""}}),
-        ] }.p2().iter().next().unwrap(), "https://www.example.com/blog/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://www.example.com/blog/wp-content/uploads/2021/01/video.mp4")
     }
 
+    #[cfg(feature = "mov")]
     #[test]
     fn test_p2_with_backslashes_and_mov() {
         assert_eq!(Xtract { json: vec![
             json!({"content": {"rendered": ""}, "excerpt": {"rendered": "https:\\/\\/www.example.com\\/wp-content\\/uploads\\/2021\\/01\\/video.mov"}}),
-        ] }.p2().iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mov")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mov")
     }
 
+    #[cfg(feature = "mov")]
     #[test]
     fn test_p2_with_backslashes_and_caps_mov() {
         assert_eq!(Xtract { json: vec![
             json!({"excerpt": {"rendered": ""}, "excerpt": {"rendered": "https:\\/\\/www.example.com\\/wp-content\\/uploads\\/2021\\/01\\/video.mov"}}),
-        ] }.p2().iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mov")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mov")
     }
 
+    #[cfg(feature = "mp4")]
     #[test]
     fn test_p2_with_backslashes_and_caps_mp4() {
         assert_eq!(Xtract { json: vec![
             json!({"content": {"rendered": ""}, "excerpt": {"rendered": "https:\\/\\/www.example.com\\/wp-content\\/uploads\\/2021\\/01\\/video.mp4"}}),
-        ] }.p2().iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mp4")
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://www.example.com/wp-content/uploads/2021/01/video.mp4")
     }
 
     #[test]
@@ -271,11 +394,12 @@ mod tests {
             Xtract {
                 json: vec![
                     json!({"link": "http://example.com/post-slug", "date": "2021-01-01t00:00:00"}),
-                ]
+                ],
+                ..Default::default()
             }
-            .p3()
+            .extract(&SlugExtractor::default())
             .len(),
-            SUPPORTED_MIME_TYPES.len() * 2
+            MimeRegistry::default().len() * 2
         )
     }
 
@@ -284,9 +408,195 @@ mod tests {
         assert_eq!(Xtract {
             json: vec![
                 json!({"link": "http://example.com/blog/post-slug", "date": "2021-01-01t00:00:00"}),
-            ]
+            ],
+            ..Default::default()
         }
-        .p3().len(), SUPPORTED_MIME_TYPES.len() * 2)
+        .extract(&SlugExtractor::default()).len(), MimeRegistry::default().len() * 2)
+    }
+
+    #[cfg(feature = "mp4")]
+    #[test]
+    fn test_p2_with_multisite_and_undated_folders() {
+        let urls = Xtract { json: vec![
+            json!({"content": {"rendered": "<video src=\"https://example.com/wp-content/uploads/sites/2/2021/01/a.mp4\"></video><a href='https://example.com/wp-content/uploads/b.mp4'>b</a>"}}),
+        ], ..Default::default() }.extract(&ContentExtractor);
+        assert_eq!(urls.len(), 2);
+        assert!(urls.contains("https://example.com/wp-content/uploads/sites/2/2021/01/a.mp4"));
+        assert!(urls.contains("https://example.com/wp-content/uploads/b.mp4"));
+    }
+
+    #[cfg(feature = "mp4")]
+    #[test]
+    fn test_p2_with_photon_url() {
+        assert_eq!(Xtract { json: vec![
+            json!({"content": {"rendered": "https://i0.wp.com/example.com/wp-content/uploads/2021/01/video.mp4?ssl=1"}}),
+        ], ..Default::default() }.extract(&ContentExtractor).iter().next().unwrap(), "https://example.com/wp-content/uploads/2021/01/video.mp4")
+    }
+
+    #[cfg(feature = "mp4")]
+    #[test]
+    fn test_p2_with_learned_upload_base() {
+        let xtract = Xtract::new(
+            r#"[
+            {"source_url": "https://cdn.example.com/media/2021/01/image.jpg", "media_details": {"file": "2021/01/image.jpg"}},
+            {"content": {"rendered": "https://cdn.example.com/media/2021/02/video.mp4"}}
+        ]"#,
+        );
+        assert_eq!(
+            xtract.extract(&ContentExtractor).iter().next().unwrap(),
+            "https://cdn.example.com/media/2021/02/video.mp4"
+        )
+    }
+
+    #[cfg(all(feature = "mp4", feature = "mov"))]
+    #[test]
+    fn test_p2_with_longer_extensions() {
+        let urls = Xtract::new(
+            r#"[
+            {"source_url": "https://cdn.example.com/media/2021/01/image.jpg", "media_details": {"file": "2021/01/image.jpg"}},
+            {"content": {"rendered": "https://cdn.example.com/media/2021/02/a.mp4x https://cdn.example.com/media/2021/02/b.mp4.jpg https://example.com/wp-content/uploads/2021/02/c.mp4.jpg https://cdn.example.com/media/2021/02/d.mp4?v=1 https://example.com/wp-content/uploads/2021/02/e.mov"}}
+        ]"#,
+        )
+        .extract(&ContentExtractor);
+        assert_eq!(
+            urls,
+            HashSet::from([
+                "https://cdn.example.com/media/2021/02/d.mp4".to_string(),
+                "https://example.com/wp-content/uploads/2021/02/e.mov".to_string(),
+            ])
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[test]
+    fn test_p3_with_learned_upload_bases() {
+        let urls = Xtract {
+            json: vec![
+                json!({"link": "http://example.com/post-slug", "date": "2021-01-01t00:00:00"}),
+            ],
+            upload_bases: HashSet::from([
+                UploadBase {
+                    url: "https://bucket.s3.amazonaws.com/wp-content/uploads".to_string(),
+                    dated: true,
+                },
+                UploadBase {
+                    url: "http://example.com/files".to_string(),
+                    dated: false,
+                },
+            ]),
+            ..Default::default()
+        }
+        .extract(&SlugExtractor::default());
+        assert_eq!(urls.len(), MimeRegistry::default().len() * 4);
+        assert!(urls
+            .contains("https://bucket.s3.amazonaws.com/wp-content/uploads/2021/01/post-slug.mp4"));
+        assert!(urls.contains("http://example.com/files/post-slug.mp4"));
+    }
+
+    #[test]
+    fn test_upload_base_learn() {
+        assert_eq!(
+            UploadBase::learn(
+                "https://cdn.example.com/media/2021/01/image.jpg",
+                Some("2021/01/image.jpg")
+            ),
+            Some(UploadBase {
+                url: "https://cdn.example.com/media".to_string(),
+                dated: true
+            })
+        );
+        assert_eq!(
+            UploadBase::learn(
+                "https://example.com/wp-content/uploads/sites/3/image.jpg",
+                None
+            ),
+            Some(UploadBase {
+                url: "https://example.com/wp-content/uploads/sites/3".to_string(),
+                dated: false
+            })
+        );
+        assert_eq!(
+            UploadBase::learn(
+                "https://i2.wp.com/example.com/wp-content/uploads/2021/01/image.jpg?w=300",
+                None
+            ),
+            Some(UploadBase {
+                url: "https://example.com/wp-content/uploads".to_string(),
+                dated: true
+            })
+        );
+        assert_eq!(
+            UploadBase::learn("https://example.com/image.jpg", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_run_with_mime_types() {
+        let xtract = Xtract::new(r#"[
+            {"source_url": "http://example.com/wp-content/uploads/2021/01/image.jpg"},
+            {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/a.mkv http://example.com/wp-content/uploads/2021/01/b.mp4"}}
+        ]"#)
+        .with_mime_types(&MimeRegistry::from_iter([("mkv", "video/x-matroska")]));
+
+        assert_eq!(
+            xtract.run(),
+            HashSet::from([
+                "http://example.com/wp-content/uploads/2021/01/image.mkv".to_string(),
+                "http://example.com/wp-content/uploads/2021/01/a.mkv".to_string(),
+            ])
+        );
+    }
+
+    #[cfg(all(feature = "mp3", feature = "jpg"))]
+    #[test]
+    fn test_run_with_audio_and_image_kinds() {
+        let json = r#"[
+            {"source_url": "http://example.com/wp-content/uploads/2021/01/image.jpg"},
+            {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/episode.mp3 http://example.com/wp-content/uploads/2021/01/b.mp4"}}
+        ]"#;
+
+        let audio = Xtract::new(json)
+            .with_mime_types(&MimeRegistry::for_kind(crate::MediaKind::Audio))
+            .run();
+        assert!(audio.contains("http://example.com/wp-content/uploads/2021/01/episode.mp3"));
+        assert!(audio.contains("http://example.com/wp-content/uploads/2021/01/image.mp3"));
+        assert!(!audio.iter().any(|url| url.ends_with(".mp4")));
+
+        let images = Xtract::new(json)
+            .with_mime_types(&MimeRegistry::for_kind(crate::MediaKind::Image))
+            .run();
+        assert!(images.contains("http://example.com/wp-content/uploads/2021/01/image.jpg"));
+        assert!(!images.iter().any(|url| url.ends_with(".mp3")));
+    }
+
+    #[test]
+    fn test_run_with_no_mime_types() {
+        assert!(Xtract::new(
+            r#"[
+            {"source_url": "http://example.com/wp-content/uploads/2021/01/image.jpg"},
+            {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/a.mp4"}},
+            {"link": "http://example.com/post-slug", "date": "2021-01-01T00:00:00"}
+        ]"#
+        )
+        .with_mime_types(&MimeRegistry::empty())
+        .run()
+        .is_empty());
+    }
+
+    #[cfg(all(feature = "mp4", feature = "mov"))]
+    #[test]
+    fn test_run_preserves_case() {
+        let urls = Xtract::new(r#"[
+            {"source_url": "https://example.com/wp-content/uploads/2021/01/Video-Final.MP4"},
+            {"content": {"rendered": "<a href=\"https://example.com/wp-content/uploads/2021/01/Trailer.Mov\">Trailer</a>"}},
+            {"link": "https://example.com/My-Post", "date": "2021-01-01T00:00:00"}
+        ]"#)
+        .run();
+
+        assert!(urls.contains("https://example.com/wp-content/uploads/2021/01/Video-Final.MP4"));
+        assert!(urls.contains("https://example.com/wp-content/uploads/2021/01/Trailer.Mov"));
+        assert!(urls.contains("https://example.com/wp-content/uploads/2021/01/My-Post.mp4"));
     }
 
     #[test]
@@ -298,7 +608,58 @@ mod tests {
                 {"_embedded": {"wp:featuredmedia": [{"source_url": "http://example.com/wp-content/uploads/2021/01/video-2.mp4"}]}},
                 {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/video-3.mp4"}, "excerpt": {"rendered": ""}},
                 {"link": "http://example.com/post-slug", "date": "2021-01-01T00:00:00"}
-            ]"#).run().len(), 3 + SUPPORTED_MIME_TYPES.len() * 3
+            ]"#).with_mime_types(&MimeRegistry::from_iter([("mp4", "video/mp4"), ("mov", "video/quicktime")])).run().len(), 3 + 2 * 3
     )
     }
+
+    #[test]
+    fn test_candidates_with_budget() {
+        let xtract = Xtract::new(
+            r#"[
+                {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/a.mp4 http://example.com/wp-content/uploads/2021/01/b.mp4"}, "link": "http://example.com/first", "date": "2021-01-01T00:00:00"},
+                {"content": {"rendered": "http://example.com/wp-content/uploads/2021/01/c.mp4"}, "link": "http://example.com/second", "date": "2021-01-01T00:00:00"}
+            ]"#,
+        )
+        .with_mime_types(&MimeRegistry::from_iter([("mp4", "video/mp4")]));
+        assert_eq!(xtract.candidates().len(), 7);
+
+        let urls = |budget: FinderBudget| {
+            Xtract {
+                json: xtract.json.clone(),
+                mime_types: xtract.mime_types.clone(),
+                ..Default::default()
+            }
+            .with_budget(&budget)
+            .candidates()
+            .into_iter()
+            .map(|candidate| candidate.url)
+            .collect::<Vec<_>>()
+        };
+        let content_urls = [
+            "http://example.com/wp-content/uploads/2021/01/a.mp4",
+            "http://example.com/wp-content/uploads/2021/01/b.mp4",
+            "http://example.com/wp-content/uploads/2021/01/c.mp4",
+        ];
+        assert_eq!(
+            urls(FinderBudget {
+                min_confidence: 0.5,
+                max_guesses_per_item: None,
+            }),
+            content_urls
+        );
+        assert_eq!(
+            urls(FinderBudget {
+                min_confidence: 0.0,
+                max_guesses_per_item: Some(1),
+            }),
+            [content_urls[0], content_urls[2]]
+        );
+        // The slug guesses only fill the budget left by the content links.
+        let urls = urls(FinderBudget {
+            min_confidence: 0.0,
+            max_guesses_per_item: Some(3),
+        });
+        assert_eq!(urls.len(), 6);
+        assert_eq!(urls[..3], content_urls);
+    }
 }
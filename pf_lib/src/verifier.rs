@@ -0,0 +1,358 @@
+use futures_util::future::LocalBoxFuture;
+use futures_util::FutureExt;
+use std::sync::Arc;
+
+use crate::link_utils;
+use crate::{FinderConfig, FinderMatch, FinderProbe, ProbeOutcome};
+
+/// What a `Verifier` concluded about a candidate URL.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    /// The URL is a file of a registered MIME type.
+    Confirmed(FinderMatch),
+    /// The URL is missing or of another type.
+    Rejected,
+    /// The verifier failed to tell (e.g. the server is unavailable), the URL is verified again by the next scan.
+    Inconclusive,
+    /// The verifier cannot tell, the next verifier of the chain decides.
+    Undecided,
+}
+
+/// What a `Verifier` can use to verify a candidate URL.
+pub struct VerifyContext<'a> {
+    /// A `reqwest::Client` that does not follow redirects (cf. `FinderRedirects`).
+    pub client: &'a reqwest::Client,
+    /// The `FinderConfig` of the scan (e.g. its MIME types and probe cache).
+    pub config: &'a FinderConfig,
+}
+
+/// Confirms or rejects the candidate URLs extracted by the `Extractor`s.
+///
+/// Verifiers are chained (cf. `Verifiers`): the first verdict other than `Verdict::Undecided` wins, so that an
+/// allow-list verifier can reject URLs before they are probed, or a verifier backed by another cache can confirm
+/// them without requests.
+pub trait Verifier: Send + Sync {
+    /// Returns the name of the verifier (e.g. `head`).
+    fn name(&self) -> &str;
+
+    /// Verifies a candidate URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The candidate URL.
+    /// * `context` - The `VerifyContext` of the scan.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Verdict`, or an error reported without ending the scan (e.g. a content
+    /// mismatch).
+    fn verify<'a>(
+        &'a self,
+        url: &'a str,
+        context: &'a VerifyContext<'a>,
+    ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>>;
+}
+
+/// Converts the outcome of a probe to a `Verdict`.
+fn verdict(outcome: ProbeOutcome) -> Verdict {
+    match outcome {
+        ProbeOutcome::Found(found) => Verdict::Confirmed(found),
+        ProbeOutcome::Absent => Verdict::Rejected,
+        ProbeOutcome::Unknown => Verdict::Inconclusive,
+    }
+}
+
+/// Verifies candidate URLs by the `Content-Type` header of a HEAD request (cf. `FinderVerification::Head`).
+#[derive(Clone, Copy, Debug)]
+pub struct HeadVerifier {
+    /// Whether to retry HEAD requests rejected with a 403, 405 or 501 status as single byte range GET requests.
+    pub get_fallback: bool,
+}
+
+impl Default for HeadVerifier {
+    fn default() -> Self {
+        Self { get_fallback: true }
+    }
+}
+
+impl Verifier for HeadVerifier {
+    fn name(&self) -> &str {
+        "head"
+    }
+
+    fn verify<'a>(
+        &'a self,
+        url: &'a str,
+        context: &'a VerifyContext<'a>,
+    ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>> {
+        async move {
+            let config = context.config;
+            let outcome = link_utils::probe_link(
+                context.client,
+                url,
+                &config.mime_types,
+                &config.redirects,
+                &config.retry,
+                self.get_fallback,
+                config.probe_cache.as_ref(),
+            )
+            .await;
+            Ok(verdict(outcome))
+        }
+        .boxed_local()
+    }
+}
+
+/// Verifies candidate URLs by the magic bytes of a ranged GET request (cf. `FinderVerification::Sniff`).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SniffVerifier;
+
+impl Verifier for SniffVerifier {
+    fn name(&self) -> &str {
+        "sniff"
+    }
+
+    fn verify<'a>(
+        &'a self,
+        url: &'a str,
+        context: &'a VerifyContext<'a>,
+    ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>> {
+        async move {
+            let config = context.config;
+            let outcome = link_utils::sniff_link(
+                context.client,
+                url,
+                &config.mime_types,
+                &config.redirects,
+                &config.retry,
+                config.probe_cache.as_ref(),
+            )
+            .await?;
+            Ok(verdict(outcome))
+        }
+        .boxed_local()
+    }
+}
+
+/// Confirms candidate URLs without requests, trusting the `Extractor`s. The MIME type is the one of the extension,
+/// and URLs without a registered extension are rejected.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrustVerifier;
+
+impl Verifier for TrustVerifier {
+    fn name(&self) -> &str {
+        "trust"
+    }
+
+    fn verify<'a>(
+        &'a self,
+        url: &'a str,
+        context: &'a VerifyContext<'a>,
+    ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>> {
+        let found = context
+            .config
+            .mime_types
+            .get_by_url(url)
+            .map(|mime_type| FinderMatch {
+                url: url.to_string(),
+                mime_type: mime_type.to_string(),
+                probe: FinderProbe::Trusted,
+                final_url: url.to_string(),
+                redirects: vec![],
+            });
+        let verdict = found.map_or(Verdict::Rejected, Verdict::Confirmed);
+        futures_util::future::ready(Ok(verdict)).boxed_local()
+    }
+}
+
+/// Chain of `Verifier`s, itself a `Verifier` whose verdict is the first one other than `Verdict::Undecided`.
+///
+/// The default chain is empty, and always undecided. Clones share the same verifiers.
+#[derive(Clone, Default)]
+pub struct Verifiers(Vec<Arc<dyn Verifier>>);
+
+impl std::fmt::Debug for Verifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl FromIterator<Arc<dyn Verifier>> for Verifiers {
+    fn from_iter<I: IntoIterator<Item = Arc<dyn Verifier>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Verifiers {
+    /// Appends a verifier.
+    pub fn with(mut self, verifier: impl Verifier + 'static) -> Self {
+        self.push(Arc::new(verifier));
+        self
+    }
+
+    /// Appends a shared verifier.
+    pub fn push(&mut self, verifier: Arc<dyn Verifier>) {
+        self.0.push(verifier);
+    }
+
+    /// Returns an iterator over the verifiers.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Verifier> {
+        self.0.iter().map(|verifier| verifier.as_ref())
+    }
+
+    /// Returns an iterator over the names of the verifiers.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|verifier| verifier.name())
+    }
+
+    /// Returns the number of verifiers.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether there is no verifier.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Verifier for Verifiers {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    fn verify<'a>(
+        &'a self,
+        url: &'a str,
+        context: &'a VerifyContext<'a>,
+    ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>> {
+        async move {
+            for verifier in self.iter() {
+                match verifier.verify(url, context).await? {
+                    Verdict::Undecided => {}
+                    verdict => return Ok(verdict),
+                }
+            }
+            Ok(Verdict::Undecided)
+        }
+        .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MimeRegistry;
+
+    /// Rejects the URLs of other hosts.
+    struct HostVerifier(&'static str);
+
+    impl Verifier for HostVerifier {
+        fn name(&self) -> &str {
+            "host"
+        }
+
+        fn verify<'a>(
+            &'a self,
+            url: &'a str,
+            _: &'a VerifyContext<'a>,
+        ) -> LocalBoxFuture<'a, Result<Verdict, Box<dyn std::error::Error>>> {
+            let verdict = match reqwest::Url::parse(url) {
+                Ok(url) if url.host_str() == Some(self.0) => Verdict::Undecided,
+                _ => Verdict::Rejected,
+            };
+            futures_util::future::ready(Ok(verdict)).boxed_local()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verifiers() {
+        let config = FinderConfig {
+            mime_types: MimeRegistry::from_iter([("mp4", "video/mp4")]),
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+        let context = VerifyContext {
+            client: &client,
+            config: &config,
+        };
+        let verifiers = Verifiers::default()
+            .with(HostVerifier("example.com"))
+            .with(TrustVerifier);
+        assert_eq!(format!("{verifiers:?}"), r#"["host", "trust"]"#);
+
+        let verdict = verifiers
+            .verify("http://example.com/video.mp4", &context)
+            .await
+            .unwrap();
+        let Verdict::Confirmed(found) = verdict else {
+            panic!("unexpected verdict: {verdict:?}");
+        };
+        assert_eq!(found.mime_type, "video/mp4");
+        assert_eq!(found.probe, FinderProbe::Trusted);
+
+        for url in ["http://other.com/video.mp4", "http://example.com/page.html"] {
+            assert_eq!(
+                verifiers.verify(url, &context).await.unwrap(),
+                Verdict::Rejected
+            );
+        }
+        assert_eq!(
+            Verifiers::default()
+                .verify("http://example.com/video.mp4", &context)
+                .await
+                .unwrap(),
+            Verdict::Undecided
+        );
+    }
+
+    #[cfg(feature = "mp4")]
+    #[tokio::test]
+    async fn test_head_verifier() {
+        let mut server = mockito::Server::new_async().await;
+        let found_mock = server
+            .mock("HEAD", "/video.mp4")
+            .with_header("Content-Type", "video/mp4")
+            .create_async()
+            .await;
+        let missing_mock = server
+            .mock("HEAD", "/missing.mp4")
+            .with_status(404)
+            .create_async()
+            .await;
+        let unavailable_mock = server
+            .mock("HEAD", "/unavailable.mp4")
+            .with_status(503)
+            .create_async()
+            .await;
+        let config = FinderConfig::default();
+        let client = link_utils::probe_client().unwrap();
+        let context = VerifyContext {
+            client: &client,
+            config: &config,
+        };
+
+        let verdict = HeadVerifier::default()
+            .verify(&format!("{}/video.mp4", server.url()), &context)
+            .await
+            .unwrap();
+        assert!(matches!(verdict, Verdict::Confirmed(found) if found.probe == FinderProbe::Head));
+        assert_eq!(
+            HeadVerifier::default()
+                .verify(&format!("{}/missing.mp4", server.url()), &context)
+                .await
+                .unwrap(),
+            Verdict::Rejected
+        );
+        assert_eq!(
+            HeadVerifier::default()
+                .verify(&format!("{}/unavailable.mp4", server.url()), &context)
+                .await
+                .unwrap(),
+            Verdict::Inconclusive
+        );
+        found_mock.assert_async().await;
+        missing_mock.assert_async().await;
+        unavailable_mock.assert_async().await;
+    }
+}
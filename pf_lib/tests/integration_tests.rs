@@ -3,16 +3,16 @@ mod tests {
     use std::process::Command;
 
     #[test]
-    fn test_compile_fail_with_no_features() {
+    fn test_compile_warning_with_no_features() {
         let output = Command::new("cargo")
             .arg("build")
             .arg("--no-default-features")
             .output()
             .expect("Failed to execute cargo build");
 
-        assert!(!output.status.success());
+        assert!(output.status.success());
         let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.contains("Error: At least one feature must be enabled."));
+        assert!(stderr.contains("No type feature is enabled, so there are no default MIME types."));
     }
 
     #[test]